@@ -0,0 +1,51 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use logus::{Correctness, PackedWord};
+
+const DICT: &str = include_str!("../dictionary.txt");
+
+fn words() -> Vec<&'static str> {
+    DICT.lines()
+        .map(|line| {
+            line.split_once(' ')
+                .expect("every line is word + space + frequency")
+                .0
+        })
+        .collect()
+}
+
+fn compute(c: &mut Criterion) {
+    let words = words();
+    let packed: Vec<_> = words.iter().map(|&w| PackedWord::new(w)).collect();
+
+    let mut group = c.benchmark_group("compute");
+    group.bench_function("str", |b| {
+        b.iter(|| Correctness::compute(black_box("cigar"), black_box("tares")))
+    });
+    group.bench_function("packed", |b| {
+        let guess = PackedWord::new("tares");
+        let answer = PackedWord::new("cigar");
+        b.iter(|| black_box(guess).compute(black_box(answer)))
+    });
+
+    group.throughput(Throughput::Elements(words.len() as u64));
+    group.bench_function("str/dictionary", |b| {
+        b.iter(|| {
+            for &answer in &words {
+                black_box(Correctness::compute(answer, black_box("tares")));
+            }
+        })
+    });
+    group.bench_function("packed/batch", |b| {
+        let guess = PackedWord::new("tares");
+        let mut out = vec![0; packed.len()];
+        b.iter(|| black_box(guess).compute_batch(&packed, &mut out))
+    });
+    group.bench_function("packed/pattern_counts", |b| {
+        let guess = PackedWord::new("tares");
+        b.iter(|| black_box(guess).pattern_counts(&packed))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, compute);
+criterion_main!(benches);
@@ -0,0 +1,92 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use logus::{algorithms, Guess, Guesser, Pattern, Wordle};
+use std::borrow::Cow;
+
+const GAMES: &str = include_str!("../answers.txt");
+
+// The answer every single-guess benchmark is played against. "tares" leaves a few hundred
+// candidates for "cigar", which is enough work to be representative without the slower
+// algorithms taking minutes per sample.
+const ANSWER: &str = "cigar";
+const OPENER: &str = "tares";
+
+// How many answers the full-game sweep plays through.
+const SWEEP: usize = 100;
+
+fn opening() -> Vec<Guess<'static>> {
+    vec![Guess {
+        word: Cow::Borrowed(OPENER),
+        mask: Pattern::compute(ANSWER, OPENER),
+    }]
+}
+
+fn matches(c: &mut Criterion) {
+    let guess = &opening()[0];
+    let mut group = c.benchmark_group("matches");
+    group.bench_function("hit", |b| b.iter(|| guess.matches(black_box(ANSWER))));
+    group.bench_function("miss", |b| b.iter(|| guess.matches(black_box("rebut"))));
+    group.finish();
+}
+
+fn first_guess(c: &mut Criterion) {
+    let history = opening();
+    let mut group = c.benchmark_group("first_guess");
+    group.sample_size(10);
+
+    macro_rules! bench {
+        ($name:literal, $guesser:ty) => {
+            group.bench_function($name, |b| {
+                b.iter_batched(
+                    <$guesser>::new,
+                    |mut g| g.guess(black_box(&history)),
+                    BatchSize::PerIteration,
+                )
+            });
+        };
+    }
+
+    bench!("naive", algorithms::Naive);
+    bench!("allocs", algorithms::Allocs);
+    bench!("vecrem", algorithms::Vecrem);
+    bench!("once", algorithms::OnceInit);
+    bench!("precalc", algorithms::Precalc);
+    bench!("weight", algorithms::Weight);
+    bench!("enum", algorithms::Enumerate);
+    bench!("cutoff", algorithms::Cutoff);
+    bench!("popular", algorithms::Popular);
+    bench!("sigmoid", algorithms::Sigmoid);
+    bench!("mcts", algorithms::Mcts);
+    group.finish();
+}
+
+fn games(c: &mut Criterion) {
+    let w = Wordle::new();
+    let answers: Vec<_> = GAMES.split_whitespace().take(SWEEP).collect();
+    let mut group = c.benchmark_group("games");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(answers.len() as u64));
+
+    // Only the algorithms that finish a game in milliseconds; the others would make a single
+    // sample take minutes.
+    macro_rules! bench {
+        ($name:literal, $guesser:ty) => {
+            group.bench_function($name, |b| {
+                b.iter(|| {
+                    answers
+                        .iter()
+                        .filter_map(|&answer| w.play(answer, <$guesser>::new()))
+                        .sum::<usize>()
+                })
+            });
+        };
+    }
+
+    bench!("enum", algorithms::Enumerate);
+    bench!("cutoff", algorithms::Cutoff);
+    bench!("popular", algorithms::Popular);
+    bench!("sigmoid", algorithms::Sigmoid);
+    group.finish();
+}
+
+criterion_group!(benches, matches, first_guess, games);
+criterion_main!(benches);
@@ -1,33 +1,57 @@
-use crate::{enumerate_mask, Correctness, Guess, Guesser, DICT, MAX_MASK_ENUM};
+use crate::entropy;
+use crate::prior::{weigh_sorted, AnswerPrior, RawFrequency};
+use crate::{AnytimeGuesser, BudgetedGuess, Guess, Guesser, NoCandidates, Pattern, MAX_MASK_ENUM};
 use once_cell::sync::OnceCell;
 use std::borrow::Cow;
+use std::time::{Duration, Instant};
 
-static INITIAL: OnceCell<Vec<(&'static str, usize)>> = OnceCell::new();
-static PATTERNS: OnceCell<Vec<[Correctness; 5]>> = OnceCell::new();
+static INITIAL: OnceCell<Vec<(&'static str, f64)>> = OnceCell::new();
 
 pub struct Cutoff {
-    remaining: Cow<'static, Vec<(&'static str, usize)>>,
-    patterns: Cow<'static, Vec<[Correctness; 5]>>,
+    remaining: Cow<'static, [(&'static str, f64)]>,
+    probe: bool,
+    aggressiveness: f64,
+}
+
+impl Default for Cutoff {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Cutoff {
     pub fn new() -> Self {
         Self {
-            remaining: Cow::Borrowed(INITIAL.get_or_init(|| {
-                let mut words = Vec::from_iter(DICT.lines().map(|line| {
-                    let (word, count) = line
-                        .split_once(' ')
-                        .expect("every line is word + space + frequency");
-                    let count: usize = count.parse().expect("every count is a number");
-                    // TODO: apply sigmoid to counts
-                    (word, count)
-                }));
-                words.sort_unstable_by_key(|&(_, count)| std::cmp::Reverse(count));
-                words
-            })),
-            patterns: Cow::Borrowed(PATTERNS.get_or_init(|| Correctness::patterns().collect())),
+            remaining: Cow::Borrowed(INITIAL.get_or_init(|| weigh_sorted(&RawFrequency))),
+            probe: false,
+            aggressiveness: 1.0,
+        }
+    }
+
+    pub fn with_prior<P: AnswerPrior + ?Sized>(prior: &P) -> Self {
+        Self {
+            remaining: Cow::Owned(weigh_sorted(prior)),
+            probe: false,
+            aggressiveness: 1.0,
         }
     }
+
+    /// Also considers guessing words that can no longer be the answer, when one of them is
+    /// expected to finish the game sooner than the best remaining candidate.
+    pub fn probing(mut self) -> Self {
+        self.probe = true;
+        self
+    }
+
+    /// Stops scoring candidates once none of the rest could beat the best so far by more than a
+    /// factor of `aggressiveness`, assuming each yields as much information as is left. The
+    /// default of 1 never skips a candidate that could win; larger values trade accuracy for
+    /// speed.
+    pub fn pruning(mut self, aggressiveness: f64) -> Self {
+        assert!(aggressiveness >= 1.0, "aggressiveness below 1 prunes nothing extra");
+        self.aggressiveness = aggressiveness;
+        self
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -37,7 +61,28 @@ struct Candidate {
 }
 
 impl Guesser for Cutoff {
-    fn guess(&mut self, history: &[Guess]) -> String {
+    fn guess(&mut self, history: &[Guess]) -> Result<String, NoCandidates> {
+        Ok(self.guess_until(history, None)?.guess)
+    }
+}
+
+impl AnytimeGuesser for Cutoff {
+    fn guess_with_budget(
+        &mut self,
+        history: &[Guess],
+        budget: Duration,
+    ) -> Result<BudgetedGuess, NoCandidates> {
+        self.guess_until(history, Instant::now().checked_add(budget))
+    }
+}
+
+impl Cutoff {
+    /// Guesses, but stops scoring candidates at `deadline`.
+    fn guess_until(
+        &mut self,
+        history: &[Guess],
+        deadline: Option<Instant>,
+    ) -> Result<BudgetedGuess, NoCandidates> {
         if let Some(last) = history.last() {
             if matches!(self.remaining, Cow::Owned(_)) {
                 self.remaining
@@ -54,18 +99,38 @@ impl Guesser for Cutoff {
             }
         }
         if history.is_empty() {
-            self.patterns = Cow::Borrowed(PATTERNS.get().unwrap());
-            return "tares".to_string();
-        } else {
-            assert!(!self.patterns.is_empty());
+            return Ok(BudgetedGuess {
+                guess: crate::opener().to_string(),
+                scored: 0,
+                candidates: self.remaining.len(),
+                probed: None,
+                complete: true,
+            });
         }
 
-        let remaining_count: usize = self.remaining.iter().map(|&(_, c)| c).sum();
+        let remaining_count: f64 = self.remaining.iter().map(|&(_, c)| c).sum();
+
+        // No guess can be expected to reveal more than is left to know, nor more than it takes to
+        // tell every mask apart. `remaining` is ordered by weight, so once even that much
+        // information times `p_word` can't beat the best goodness so far, no later word can.
+        let max_entropy = entropy::remaining_entropy(self.remaining.iter().map(|&(_, c)| c))
+            .min((self.remaining.len().min(MAX_MASK_ENUM) as f64).log2());
 
         let mut best: Option<Candidate> = None;
-        let mut i = 0;
-        let stop = (self.remaining.len() / 3).max(20);
+        let mut scored = 0;
+        let mut complete = true;
         for &(word, count) in &*self.remaining {
+            if scored > 0 && deadline.is_some_and(|d| Instant::now() >= d) {
+                complete = false;
+                break;
+            }
+            let p_word = count / remaining_count;
+            if let Some(c) = best {
+                if p_word * max_entropy <= self.aggressiveness * c.goodness {
+                    break;
+                }
+            }
+
             // considering a world where we _did_ guess `word` and got `pattern` as the
             // correctness. now, compute what _then_ is left.
 
@@ -73,25 +138,24 @@ impl Guesser for Cutoff {
             // that result in that pattern, we can instead keep a running total for each pattern
             // simultaneously by storing them in an array. We can do this since each candidate-word
             // pair deterministically produces only one mask.
-            let mut totals = [0usize; MAX_MASK_ENUM];
+            let mut totals = [0.0f64; MAX_MASK_ENUM];
             for (candidate, count) in &*self.remaining {
-                let idx = enumerate_mask(&Correctness::compute(candidate, word));
+                let idx = Pattern::compute(candidate, word).index();
                 totals[idx] += count;
             }
 
-            assert_eq!(totals.iter().sum::<usize>(), remaining_count, "{}", word);
+            let total: f64 = totals.iter().sum();
+            assert!((total - remaining_count).abs() <= 1e-9 * remaining_count, "{}", word);
 
             let sum: f64 = totals
                 .into_iter()
-                .filter(|t| *t != 0)
+                .filter(|t| *t != 0.0)
                 .map(|t| {
-                    // TODO: apply sigmoid
-                    let p_of_this_pattern = t as f64 / remaining_count as f64;
+                    let p_of_this_pattern = t / remaining_count;
                     p_of_this_pattern * p_of_this_pattern.log2()
                 })
                 .sum();
 
-            let p_word = count as f64 / remaining_count as f64;
             let entropy = -sum;
             // TODO: this should be (minimizing):
             // (p_word * (history.len() + 1)) + ((1 - p_word) * estimate_remaining_guesses(remaining_entropy))
@@ -106,12 +170,22 @@ impl Guesser for Cutoff {
             } else {
                 best = Some(Candidate { word, goodness });
             }
-
-            i += 1;
-            if i >= stop {
-                break;
-            }
+            scored += 1;
+        }
+        let mut guess = best.ok_or(NoCandidates)?.word;
+        let mut probed = None;
+        if self.probe && complete {
+            let (probe, scanned) = entropy::probe_until(&self.remaining, guess, deadline);
+            guess = probe.unwrap_or(guess);
+            complete = scanned == entropy::guess_pool().len();
+            probed = Some(scanned);
         }
-        best.unwrap().word.to_string()
+        Ok(BudgetedGuess {
+            guess: guess.to_string(),
+            scored,
+            candidates: self.remaining.len(),
+            probed,
+            complete,
+        })
     }
 }
@@ -1,27 +1,44 @@
-use crate::{enumerate_mask, Correctness, Guess, Guesser, DICT, MAX_MASK_ENUM};
+use crate::entropy;
+use crate::prior::{weigh_sorted, AnswerPrior, RawFrequency};
+use crate::{AnytimeGuesser, BudgetedGuess, Guess, Guesser, NoCandidates, Pattern, MAX_MASK_ENUM};
 use once_cell::sync::OnceCell;
 use std::borrow::Cow;
+use std::time::{Duration, Instant};
 
-static INITIAL: OnceCell<Vec<(&'static str, usize)>> = OnceCell::new();
+static INITIAL: OnceCell<Vec<(&'static str, f64)>> = OnceCell::new();
 
 pub struct Enumerate {
-    remaining: Cow<'static, Vec<(&'static str, usize)>>,
+    remaining: Cow<'static, [(&'static str, f64)]>,
+    probe: bool,
+}
+
+impl Default for Enumerate {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Enumerate {
     pub fn new() -> Self {
         Self {
-            remaining: Cow::Borrowed(INITIAL.get_or_init(|| {
-                Vec::from_iter(DICT.lines().map(|line| {
-                    let (word, count) = line
-                        .split_once(' ')
-                        .expect("every line is word + space + frequency");
-                    let count: usize = count.parse().expect("every count is a number");
-                    (word, count)
-                }))
-            })),
+            remaining: Cow::Borrowed(INITIAL.get_or_init(|| weigh_sorted(&RawFrequency))),
+            probe: false,
         }
     }
+
+    pub fn with_prior<P: AnswerPrior + ?Sized>(prior: &P) -> Self {
+        Self {
+            remaining: Cow::Owned(weigh_sorted(prior)),
+            probe: false,
+        }
+    }
+
+    /// Also considers guessing words that can no longer be the answer, when one of them is
+    /// expected to finish the game sooner than the best remaining candidate.
+    pub fn probing(mut self) -> Self {
+        self.probe = true;
+        self
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -31,7 +48,28 @@ struct Candidate {
 }
 
 impl Guesser for Enumerate {
-    fn guess(&mut self, history: &[Guess]) -> String {
+    fn guess(&mut self, history: &[Guess]) -> Result<String, NoCandidates> {
+        Ok(self.guess_until(history, None)?.guess)
+    }
+}
+
+impl AnytimeGuesser for Enumerate {
+    fn guess_with_budget(
+        &mut self,
+        history: &[Guess],
+        budget: Duration,
+    ) -> Result<BudgetedGuess, NoCandidates> {
+        self.guess_until(history, Instant::now().checked_add(budget))
+    }
+}
+
+impl Enumerate {
+    /// Guesses, but stops scoring candidates at `deadline`.
+    fn guess_until(
+        &mut self,
+        history: &[Guess],
+        deadline: Option<Instant>,
+    ) -> Result<BudgetedGuess, NoCandidates> {
         if let Some(last) = history.last() {
             if matches!(self.remaining, Cow::Owned(_)) {
                 self.remaining
@@ -48,13 +86,25 @@ impl Guesser for Enumerate {
             }
         }
         if history.is_empty() {
-            return "tares".to_string();
+            return Ok(BudgetedGuess {
+                guess: crate::opener().to_string(),
+                scored: 0,
+                candidates: self.remaining.len(),
+                probed: None,
+                complete: true,
+            });
         }
 
-        let remaining_count: usize = self.remaining.iter().map(|&(_, c)| c).sum();
+        let remaining_count: f64 = self.remaining.iter().map(|&(_, c)| c).sum();
 
         let mut best: Option<Candidate> = None;
+        let mut scored = 0;
+        let mut complete = true;
         for &(word, count) in &*self.remaining {
+            if scored > 0 && deadline.is_some_and(|d| Instant::now() >= d) {
+                complete = false;
+                break;
+            }
             // considering a world where we _did_ guess `word` and got `pattern` as the
             // correctness. now, compute what _then_ is left.
 
@@ -62,25 +112,25 @@ impl Guesser for Enumerate {
             // that result in that pattern, we can instead keep a running total for each pattern
             // simultaneously by storing them in an array. We can do this since each candidate-word
             // pair deterministically produces only one mask.
-            let mut totals = [0usize; MAX_MASK_ENUM];
+            let mut totals = [0.0f64; MAX_MASK_ENUM];
             for (candidate, count) in &*self.remaining {
-                let idx = enumerate_mask(&Correctness::compute(candidate, word));
+                let idx = Pattern::compute(candidate, word).index();
                 totals[idx] += count;
             }
 
-            assert_eq!(totals.iter().sum::<usize>(), remaining_count, "{}", word);
+            let total: f64 = totals.iter().sum();
+            assert!((total - remaining_count).abs() <= 1e-9 * remaining_count, "{}", word);
 
             let sum: f64 = totals
                 .into_iter()
-                .filter(|t| *t != 0)
+                .filter(|t| *t != 0.0)
                 .map(|t| {
-                    // TODO: apply sigmoid
-                    let p_of_this_pattern = t as f64 / remaining_count as f64;
+                    let p_of_this_pattern = t / remaining_count;
                     p_of_this_pattern * p_of_this_pattern.log2()
                 })
                 .sum();
 
-            let p_word = count as f64 / remaining_count as f64;
+            let p_word = count / remaining_count;
             let goodness = p_word * -sum;
             if let Some(c) = best {
                 // Is this one better?
@@ -90,7 +140,22 @@ impl Guesser for Enumerate {
             } else {
                 best = Some(Candidate { word, goodness });
             }
+            scored += 1;
+        }
+        let mut guess = best.ok_or(NoCandidates)?.word;
+        let mut probed = None;
+        if self.probe && complete {
+            let (probe, scanned) = entropy::probe_until(&self.remaining, guess, deadline);
+            guess = probe.unwrap_or(guess);
+            complete = scanned == entropy::guess_pool().len();
+            probed = Some(scanned);
         }
-        best.unwrap().word.to_string()
+        Ok(BudgetedGuess {
+            guess: guess.to_string(),
+            scored,
+            candidates: self.remaining.len(),
+            probed,
+            complete,
+        })
     }
 }
@@ -0,0 +1,314 @@
+use crate::algorithms::SigmoidParams;
+use crate::prior::{weigh_sorted, AnswerPrior};
+use crate::{Guess, Guesser, NoCandidates, PackedWord, Pattern, MAX_MASK_ENUM};
+use once_cell::sync::OnceCell;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+static INITIAL: OnceCell<Vec<(&'static str, f64)>> = OnceCell::new();
+
+// How much the entropy-based policy prior counts relative to the observed average score when
+// choosing which guess to explore next, in guesses.
+const EXPLORATION: f64 = 1.5;
+
+/// A Monte Carlo tree search over guesses.
+///
+/// Each iteration samples an answer from the prior, walks down the tree of (guess → mask)
+/// nodes choosing guesses by PUCT with each guess's `Sigmoid`-style goodness as its policy
+/// prior, and finishes the game from the first unexplored node by always guessing the most
+/// likely remaining word, like `Popular`. The guess whose subtree was explored the most is
+/// played.
+pub struct Mcts {
+    remaining: Cow<'static, [(&'static str, f64)]>,
+    iterations: usize,
+    time: Option<Duration>,
+    width: usize,
+    rng: ChaCha8Rng,
+}
+
+impl Default for Mcts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Mcts {
+    pub fn new() -> Self {
+        Self {
+            remaining: Cow::Borrowed(
+                INITIAL.get_or_init(|| weigh_sorted(&SigmoidParams::default())),
+            ),
+            iterations: 1000,
+            time: None,
+            width: 8,
+            rng: ChaCha8Rng::seed_from_u64(0),
+        }
+    }
+
+    pub fn with_prior<P: AnswerPrior + ?Sized>(prior: &P) -> Self {
+        Self {
+            remaining: Cow::Owned(weigh_sorted(prior)),
+            ..Self::new()
+        }
+    }
+
+    /// The most iterations to run for each guess.
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        assert!(iterations > 0, "at least one iteration is needed to pick a guess");
+        self.iterations = iterations;
+        self
+    }
+
+    /// The most time to spend on each guess. The search always completes at least one
+    /// iteration.
+    pub fn time_budget(mut self, time: Duration) -> Self {
+        self.time = Some(time);
+        self
+    }
+
+    /// How many of the best guesses by goodness each node considers.
+    pub fn width(mut self, width: usize) -> Self {
+        assert!(width > 0, "every node needs at least one guess");
+        self.width = width;
+        self
+    }
+
+    /// Seeds the answer sampling, so that the same seed and iteration budget always pick the
+    /// same guesses.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+        self
+    }
+}
+
+/// The candidates of a search, most likely first.
+struct Words {
+    words: Vec<&'static str>,
+    packed: Vec<PackedWord>,
+    weights: Vec<f64>,
+}
+
+struct Node {
+    /// Indices into `Words` of the answers still possible at this node, most likely first.
+    candidates: Vec<u32>,
+    /// Empty until the node is first visited after its creation.
+    actions: Vec<Action>,
+    visits: u32,
+}
+
+struct Action {
+    guess: u32,
+    prior: f64,
+    visits: u32,
+    /// The total number of guesses, this one included, over all visits.
+    cost: f64,
+    children: HashMap<Pattern, Node>,
+}
+
+impl Node {
+    fn new(candidates: Vec<u32>) -> Self {
+        Self {
+            candidates,
+            actions: Vec::new(),
+            visits: 0,
+        }
+    }
+
+    fn expand(&mut self, words: &Words, width: usize) {
+        let total: f64 = self.candidates.iter().map(|&c| words.weights[c as usize]).sum();
+        let mut scored: Vec<_> = self
+            .candidates
+            .iter()
+            .map(|&guess| {
+                let mut totals = [0.0f64; MAX_MASK_ENUM];
+                for &c in &self.candidates {
+                    totals[words.packed[guess as usize].compute(words.packed[c as usize])] +=
+                        words.weights[c as usize];
+                }
+                let entropy: f64 = -totals
+                    .into_iter()
+                    .filter(|&t| t > 0.0)
+                    .map(|t| {
+                        let p = t / total;
+                        p * p.log2()
+                    })
+                    .sum::<f64>();
+                let p_word = words.weights[guess as usize] / total;
+                // Being the answer saves a guess on top of what the guess reveals, and keeps
+                // every prior above zero.
+                (guess, p_word * entropy + p_word.max(f64::MIN_POSITIVE))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(width);
+
+        let sum: f64 = scored.iter().map(|&(_, goodness)| goodness).sum();
+        self.actions = scored
+            .into_iter()
+            .map(|(guess, goodness)| Action {
+                guess,
+                prior: goodness / sum,
+                visits: 0,
+                cost: 0.0,
+                children: HashMap::new(),
+            })
+            .collect();
+    }
+
+    /// Plays one simulated game from this node against `answer`, returning how many guesses it
+    /// took.
+    fn simulate(&mut self, answer: u32, words: &Words, width: usize) -> f64 {
+        if self.candidates.len() == 1 {
+            return 1.0;
+        }
+        if self.actions.is_empty() {
+            self.expand(words, width);
+            self.visits += 1;
+            return rollout(&self.candidates, answer, words);
+        }
+
+        let sqrt_visits = (self.visits as f64).sqrt();
+        let action = self
+            .actions
+            .iter_mut()
+            .max_by(|a, b| {
+                let score = |a: &Action| {
+                    // Unvisited guesses look free, so each gets tried at least once.
+                    let mean = if a.visits == 0 {
+                        0.0
+                    } else {
+                        a.cost / a.visits as f64
+                    };
+                    -mean + EXPLORATION * a.prior * sqrt_visits / (1 + a.visits) as f64
+                };
+                score(a).total_cmp(&score(b))
+            })
+            .expect("expanded nodes have at least one guess");
+
+        let cost = if action.guess == answer {
+            1.0
+        } else {
+            let guess = words.packed[action.guess as usize];
+            let mask = guess.compute(words.packed[answer as usize]);
+            let child = action.children.entry(Pattern::from_index(mask)).or_insert_with(|| {
+                Node::new(
+                    self.candidates
+                        .iter()
+                        .copied()
+                        .filter(|&c| guess.compute(words.packed[c as usize]) == mask)
+                        .collect(),
+                )
+            });
+            1.0 + child.simulate(answer, words, width)
+        };
+        action.visits += 1;
+        action.cost += cost;
+        self.visits += 1;
+        cost
+    }
+}
+
+/// Finishes a game by always guessing the most likely remaining candidate.
+fn rollout(candidates: &[u32], answer: u32, words: &Words) -> f64 {
+    let mut candidates = Cow::Borrowed(candidates);
+    let mut guesses = 0.0;
+    loop {
+        guesses += 1.0;
+        let guess = candidates[0];
+        if guess == answer {
+            return guesses;
+        }
+        let guess = words.packed[guess as usize];
+        let mask = guess.compute(words.packed[answer as usize]);
+        candidates
+            .to_mut()
+            .retain(|&c| guess.compute(words.packed[c as usize]) == mask);
+    }
+}
+
+impl Guesser for Mcts {
+    fn guess(&mut self, history: &[Guess]) -> Result<String, NoCandidates> {
+        if let Some(last) = history.last() {
+            if matches!(self.remaining, Cow::Owned(_)) {
+                self.remaining
+                    .to_mut()
+                    .retain(|(word, _)| last.matches(word));
+            } else {
+                self.remaining = Cow::Owned(
+                    self.remaining
+                        .iter()
+                        .filter(|(word, _)| last.matches(word))
+                        .copied()
+                        .collect(),
+                );
+            }
+        }
+        if history.is_empty() {
+            return Ok(crate::opener().to_string());
+        }
+        if self.remaining.is_empty() {
+            return Err(NoCandidates);
+        }
+
+        let words = Words {
+            words: self.remaining.iter().map(|&(word, _)| word).collect(),
+            packed: self
+                .remaining
+                .iter()
+                .map(|&(word, _)| PackedWord::new(word))
+                .collect(),
+            weights: self.remaining.iter().map(|&(_, weight)| weight).collect(),
+        };
+        let cumulative: Vec<f64> = words
+            .weights
+            .iter()
+            .scan(0.0, |sum, &w| {
+                *sum += w;
+                Some(*sum)
+            })
+            .collect();
+        let total = *cumulative.last().unwrap();
+
+        let mut root = Node::new((0..words.words.len() as u32).collect());
+        let start = Instant::now();
+        for i in 0..self.iterations {
+            if i > 0 && self.time.is_some_and(|t| start.elapsed() >= t) {
+                break;
+            }
+            let target = self.rng.gen::<f64>() * total;
+            let answer = cumulative
+                .partition_point(|&c| c <= target)
+                .min(cumulative.len() - 1);
+            root.simulate(answer as u32, &words, self.width);
+        }
+
+        let best = root
+            .actions
+            .iter()
+            .max_by(|a, b| {
+                a.visits
+                    .cmp(&b.visits)
+                    .then(b.cost.total_cmp(&a.cost))
+            })
+            .map_or(0, |a| a.guess);
+        Ok(words.words[best as usize].to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Wordle;
+
+    #[test]
+    fn seeded_games_repeat() {
+        let w = Wordle::new();
+        let play = |seed| w.play("cigar", Mcts::new().iterations(50).seed(seed));
+        let score = play(7).expect("mcts finishes the game");
+        assert!(score <= 6, "{}", score);
+        assert_eq!(play(7), Some(score));
+    }
+}
@@ -26,4 +26,35 @@ mod popular;
 pub use popular::Popular;
 
 mod sigmoid;
-pub use sigmoid::Sigmoid;
+pub use sigmoid::{Sigmoid, SigmoidParams, SigmoidRow};
+
+mod mcts;
+pub use mcts::Mcts;
+
+mod table;
+pub use table::{Table, TableGuesser};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prior::Uniform;
+    use crate::Wordle;
+
+    #[test]
+    fn plays_with_a_prior() {
+        let w = Wordle::new();
+        // `tares` leaves few words for `rates`, so the slow implementations are quick too.
+        let answer = "rates";
+        assert!(w.play(answer, Naive::with_prior(&Uniform)).is_some());
+        assert!(w.play(answer, Allocs::with_prior(&Uniform)).is_some());
+        assert!(w.play(answer, Vecrem::with_prior(&Uniform)).is_some());
+        assert!(w.play(answer, OnceInit::with_prior(&Uniform)).is_some());
+        assert!(w.play(answer, Precalc::with_prior(&Uniform)).is_some());
+        assert!(w.play(answer, Weight::with_prior(&Uniform)).is_some());
+        assert!(w.play(answer, Enumerate::with_prior(&Uniform)).is_some());
+        assert!(w.play(answer, Cutoff::with_prior(&Uniform)).is_some());
+        assert!(w.play(answer, Popular::with_prior(&Uniform)).is_some());
+        assert!(w.play(answer, Sigmoid::with_prior(&Uniform)).is_some());
+        assert!(w.play(answer, Mcts::with_prior(&Uniform)).is_some());
+    }
+}
@@ -1,20 +1,25 @@
-use crate::{Correctness, Guess, Guesser, DICT};
+use crate::prior::{weigh, AnswerPrior, RawFrequency};
+use crate::{Guess, Guesser, NoCandidates, Pattern};
 use std::{borrow::Cow, collections::HashMap};
 
 pub struct Naive {
-    remaining: HashMap<&'static str, usize>,
+    remaining: HashMap<&'static str, f64>,
+}
+
+impl Default for Naive {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Naive {
     pub fn new() -> Self {
-        Self {
+        Self::with_prior(&RawFrequency)
+    }
 
-            remaining: HashMap::from_iter(DICT.lines().map(|line| {
-                let (word, count) = line.split_once(' ')
-                    .expect("every line is word + space + freq");
-                let count: usize = count.parse().expect("every count is a number");
-                (word, count)
-            })),
+    pub fn with_prior<P: AnswerPrior + ?Sized>(prior: &P) -> Self {
+        Self {
+            remaining: HashMap::from_iter(weigh(prior)),
         }
     }
 }
@@ -26,23 +31,23 @@ struct Candidate {
 }
 
 impl Guesser for Naive {
-        fn guess(&mut self, history: &[Guess]) -> String {
+        fn guess(&mut self, history: &[Guess]) -> Result<String, NoCandidates> {
         if let Some(last) = history.last() {
             self.remaining.retain(|word, _| last.matches(word));
         }
         if history.is_empty() {
-            return "tares".to_string();
+            return Ok(crate::opener().to_string());
         }
 
-        let remaining_count: usize = self.remaining.iter().map(|(_, &c)| c).sum();
+        let remaining_count: f64 = self.remaining.values().sum();
 
         let mut best: Option<Candidate> = None;
-        for (&word, _) in &self.remaining {
+        for &word in self.remaining.keys() {
             let mut sum = 0.0;
-            for pattern in Correctness::patterns() {
+            for pattern in Pattern::all() {
                 // considering a world where we _did_ guess `word` and got `pattern` as the
                 // correctness. now, compute what _then_ is left.
-                let mut in_pattern_total = 0;
+                let mut in_pattern_total = 0.0;
                 for (candidate, count) in &self.remaining {
                     let g = Guess {
                         word: Cow::Owned(word.to_string()),
@@ -52,11 +57,10 @@ impl Guesser for Naive {
                         in_pattern_total += count;
                     }
                 }
-                if in_pattern_total == 0 {
+                if in_pattern_total == 0.0 {
                     continue;
                 }
-                // TODO: apply sigmoid
-                let p_of_this_pattern = in_pattern_total as f64 / remaining_count as f64;
+                let p_of_this_pattern = in_pattern_total / remaining_count;
                 sum += p_of_this_pattern * p_of_this_pattern.log2();
             }
             let goodness = -sum;
@@ -69,6 +73,6 @@ impl Guesser for Naive {
                 best = Some(Candidate { word, goodness });
             }
         }
-        best.unwrap().word.to_string()
+        best.map(|c| c.word.to_string()).ok_or(NoCandidates)
     }
 }
@@ -1,36 +1,38 @@
-use crate::{Guess, Guesser, DICT};
+use crate::prior::{weigh_sorted, AnswerPrior, RawFrequency};
+use crate::{Guess, Guesser, NoCandidates};
 use once_cell::sync::OnceCell;
 use std::borrow::Cow;
 
-static INITIAL: OnceCell<Vec<(&'static str, usize)>> = OnceCell::new();
+static INITIAL: OnceCell<Vec<(&'static str, f64)>> = OnceCell::new();
 
 /// a strawman algorithm which simply chooses the most popular word of the
 /// words remaining which match the most recent mask
 pub struct Popular {
-    remaining: Cow<'static, Vec<(&'static str, usize)>>,
+    remaining: Cow<'static, [(&'static str, f64)]>,
+}
+
+impl Default for Popular {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Popular {
     pub fn new() -> Self {
         Self {
-            remaining: Cow::Borrowed(INITIAL.get_or_init(|| {
-                let mut words = Vec::from_iter(DICT.lines().map(|line| {
-                    let (word, count) = line
-                        .split_once(' ')
-                        .expect("every line is word + space + frequency");
-                    let count: usize = count.parse().expect("every count is a number");
-                    // TODO: apply sigmoid to counts
-                    (word, count)
-                }));
-                words.sort_unstable_by_key(|&(_, count)| std::cmp::Reverse(count));
-                words
-            })),
+            remaining: Cow::Borrowed(INITIAL.get_or_init(|| weigh_sorted(&RawFrequency))),
+        }
+    }
+
+    pub fn with_prior<P: AnswerPrior + ?Sized>(prior: &P) -> Self {
+        Self {
+            remaining: Cow::Owned(weigh_sorted(prior)),
         }
     }
 }
 
 impl Guesser for Popular {
-    fn guess(&mut self, history: &[Guess]) -> String {
+    fn guess(&mut self, history: &[Guess]) -> Result<String, NoCandidates> {
         if let Some(last) = history.last() {
             if matches!(self.remaining, Cow::Owned(_)) {
                 self.remaining
@@ -47,9 +49,10 @@ impl Guesser for Popular {
             }
         }
         if history.is_empty() {
-            "tares".to_string()
+            Ok(crate::opener().to_string())
         } else {
-            self.remaining.first().unwrap().0.to_string()
+            let &(word, _) = self.remaining.first().ok_or(NoCandidates)?;
+            Ok(word.to_string())
         }
     }
 }
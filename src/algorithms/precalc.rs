@@ -1,30 +1,34 @@
-use crate::{Correctness, Guess, Guesser, DICT};
+use crate::prior::{weigh_sorted, AnswerPrior, RawFrequency};
+use crate::{Guess, Guesser, NoCandidates, Pattern};
 use once_cell::sync::OnceCell;
 use std::borrow::Cow;
 use std::collections::BTreeMap;
 
-static INITIAL: OnceCell<Vec<(&'static str, usize)>> = OnceCell::new();
-static MATCH: OnceCell<BTreeMap<(&'static str, &'static str, [Correctness; 5]), bool>> =
-    OnceCell::new();
+type MatchKey = (&'static str, &'static str, Pattern);
+
+static INITIAL: OnceCell<Vec<(&'static str, f64)>> = OnceCell::new();
+static MATCH: OnceCell<BTreeMap<MatchKey, bool>> = OnceCell::new();
 
 pub struct Precalc {
-    remaining: Cow<'static, Vec<(&'static str, usize)>>,
+    remaining: Cow<'static, [(&'static str, f64)]>,
+}
+
+impl Default for Precalc {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Precalc {
     pub fn new() -> Self {
         Self {
-            remaining: Cow::Borrowed(INITIAL.get_or_init(|| {
-                let mut words = Vec::from_iter(DICT.lines().map(|line| {
-                    let (word, count) = line
-                        .split_once(' ')
-                        .expect("every line is word + space + frequency");
-                    let count: usize = count.parse().expect("every count is a number");
-                    (word, count)
-                }));
-                words.sort_unstable_by_key(|&(_, count)| std::cmp::Reverse(count));
-                words
-            })),
+            remaining: Cow::Borrowed(INITIAL.get_or_init(|| weigh_sorted(&RawFrequency))),
+        }
+    }
+
+    pub fn with_prior<P: AnswerPrior + ?Sized>(prior: &P) -> Self {
+        Self {
+            remaining: Cow::Owned(weigh_sorted(prior)),
         }
     }
 }
@@ -36,7 +40,7 @@ struct Candidate {
 }
 
 impl Guesser for Precalc {
-    fn guess(&mut self, history: &[Guess]) -> String {
+    fn guess(&mut self, history: &[Guess]) -> Result<String, NoCandidates> {
         if let Some(last) = history.last() {
             if matches!(self.remaining, Cow::Owned(_)) {
                 self.remaining
@@ -53,30 +57,32 @@ impl Guesser for Precalc {
             }
         }
         if history.is_empty() {
-            return "tares".to_string();
+            return Ok(crate::opener().to_string());
         }
 
-        let remaining_count: usize = self.remaining.iter().map(|&(_, c)| c).sum();
+        let remaining_count: f64 = self.remaining.iter().map(|&(_, c)| c).sum();
 
         let mut best: Option<Candidate> = None;
         for &(word, _) in &*self.remaining {
             let mut sum = 0.0;
             // TODO: don't consider correctness patterns that had no candidates in the previous
             // iteration
-            for pattern in Correctness::patterns() {
+            for pattern in Pattern::all() {
                 // considering a world where we _did_ guess `word` and got `pattern` as the
                 // correctness. now, compute what _then_ is left.
-                let mut in_pattern_total = 0;
+                let mut in_pattern_total = 0.0;
                 for (candidate, count) in &*self.remaining {
                     let matches = MATCH.get_or_init(|| {
-                        let words = &INITIAL.get().unwrap()[..512];
+                        // `with_prior` doesn't fill `INITIAL`, but the table only needs its words.
+                        let initial = INITIAL.get_or_init(|| weigh_sorted(&RawFrequency));
+                        let words = &initial[..initial.len().min(512)];
                         let mut out = BTreeMap::new();
                         for &(word1, _) in words {
                             for &(word2, _) in words {
                                 if word2 < word1 {
                                     break;
                                 }
-                                for pattern in Correctness::patterns() {
+                                for pattern in Pattern::all() {
                                     let g = Guess {
                                         word: Cow::Borrowed(word1),
                                         mask: pattern,
@@ -89,9 +95,9 @@ impl Guesser for Precalc {
                     });
 
                     let key = if word < candidate {
-                        (&*word, *candidate, pattern)
+                        (word, *candidate, pattern)
                     } else {
-                        (*candidate, &*word, pattern)
+                        (*candidate, word, pattern)
                     };
                     if matches.get(&key).copied().unwrap_or_else(|| {
                         let g = Guess {
@@ -103,11 +109,10 @@ impl Guesser for Precalc {
                         in_pattern_total += count;
                     }
                 }
-                if in_pattern_total == 0 {
+                if in_pattern_total == 0.0 {
                     continue;
                 }
-                // TODO: apply sigmoid
-                let p_of_this_pattern = in_pattern_total as f64 / remaining_count as f64;
+                let p_of_this_pattern = in_pattern_total / remaining_count;
                 sum += p_of_this_pattern * p_of_this_pattern.log2();
             }
             // TODO: weight this by p_word
@@ -121,6 +126,6 @@ impl Guesser for Precalc {
                 best = Some(Candidate { word, goodness });
             }
         }
-        best.unwrap().word.to_string()
+        best.map(|c| c.word.to_string()).ok_or(NoCandidates)
     }
 }
@@ -1,13 +1,16 @@
-use crate::{enumerate_mask, Correctness, Guess, Guesser, DICT, MAX_MASK_ENUM};
+use crate::entropy;
+use crate::prior::{weigh_sorted, AnswerPrior};
+use crate::{dictionary, AnytimeGuesser, BudgetedGuess, Guess, Guesser, NoCandidates, Pattern, MAX_MASK_ENUM};
 use once_cell::sync::OnceCell;
 use std::borrow::Cow;
+use std::time::{Duration, Instant};
 
 static INITIAL: OnceCell<Vec<(&'static str, f64)>> = OnceCell::new();
-static PATTERNS: OnceCell<Vec<[Correctness; 5]>> = OnceCell::new();
 
 pub struct Sigmoid {
-    remaining: Cow<'static, Vec<(&'static str, f64)>>,
-    patterns: Cow<'static, Vec<[Correctness; 5]>>,
+    remaining: Cow<'static, [(&'static str, f64)]>,
+    probe: bool,
+    aggressiveness: f64,
 }
 
 impl Default for Sigmoid {
@@ -44,50 +47,116 @@ const X0: f64 = 0.00000497;
 // bigot 0.000498% -> 57.402526% (1581618)
 // wisps 0.000498% -> 57.407146% (1581620)
 // foals 0.000498% -> 57.875326% (1581823)
-fn sigmoid(p: f64) -> f64 {
-    L / (1.0 + (-K * (p - X0)).exp())
+//
+// `logus fit-sigmoid` prints the same table for any other combination.
+
+/// The logistic curve that maps a word's share of the dictionary's total frequency to how likely
+/// it is to be an answer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SigmoidParams {
+    /// The height of the curve.
+    pub l: f64,
+    /// How steep the cut-off is.
+    pub k: f64,
+    /// Where the cut-off is.
+    pub x0: f64,
+}
+
+impl Default for SigmoidParams {
+    fn default() -> Self {
+        Self { l: L, k: K, x0: X0 }
+    }
+}
+
+impl SigmoidParams {
+    pub fn apply(&self, p: f64) -> f64 {
+        self.l / (1.0 + (-self.k * (p - self.x0)).exp())
+    }
+
+    /// Every dictionary word with its raw and sigmoid probability, least frequent first.
+    pub fn table(&self) -> Vec<SigmoidRow> {
+        let sum: usize = dictionary().iter().map(|&(_, count)| count).sum();
+        let mut words = dictionary().to_vec();
+        words.sort_unstable_by_key(|&(_, count)| count);
+        words
+            .into_iter()
+            .map(|(word, count)| {
+                let p = count as f64 / sum as f64;
+                SigmoidRow {
+                    word,
+                    count,
+                    p,
+                    sigmoid: self.apply(p),
+                }
+            })
+            .collect()
+    }
+}
+
+/// One line of `SigmoidParams::table`.
+#[derive(Debug, Clone, Copy)]
+pub struct SigmoidRow {
+    pub word: &'static str,
+    pub count: usize,
+    pub p: f64,
+    pub sigmoid: f64,
+}
+
+impl std::fmt::Display for SigmoidRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {:.6}% -> {:.6}% ({})",
+            self.word,
+            100.0 * self.p,
+            100.0 * self.sigmoid,
+            self.count
+        )
+    }
 }
-const PRINT_SIGMOID: bool = false;
 
 impl Sigmoid {
     pub fn new() -> Self {
         Self {
-            remaining: Cow::Borrowed(INITIAL.get_or_init(|| {
-                let mut sum = 0;
-                let mut words = Vec::from_iter(DICT.lines().map(|line| {
-                    let (word, count) = line
-                        .split_once(' ')
-                        .expect("every line is word + space + frequency");
-                    let count: usize = count.parse().expect("every count is a number");
-                    sum += count;
-                    (word, count)
-                }));
-
-                words.sort_unstable_by_key(|&(_, count)| std::cmp::Reverse(count));
-
-                if PRINT_SIGMOID {
-                    for &(word, count) in words.iter().rev() {
-                        let p = count as f64 / sum as f64;
-                        println!(
-                            "{} {:.6}% -> {:.6}% ({})",
-                            word,
-                            100.0 * p,
-                            100.0 * sigmoid(p),
-                            count
-                        );
-                    }
-                }
+            remaining: Cow::Borrowed(
+                INITIAL.get_or_init(|| weigh_sorted(&SigmoidParams::default())),
+            ),
+            probe: false,
+            aggressiveness: 1.0,
+        }
+    }
 
-                let words: Vec<_> = words
-                    .into_iter()
-                    .map(|(word, count)| (word, sigmoid(count as f64 / sum as f64)))
-                    .collect();
+    pub fn with_params(params: SigmoidParams) -> Self {
+        if params == SigmoidParams::default() {
+            return Self::new();
+        }
+        Self::with_prior(&params)
+    }
 
-                words
-            })),
-            patterns: Cow::Borrowed(PATTERNS.get_or_init(|| Correctness::patterns().collect())),
+    pub fn with_prior<P: AnswerPrior + ?Sized>(prior: &P) -> Self {
+        Self {
+            remaining: Cow::Owned(weigh_sorted(prior)),
+            probe: false,
+            aggressiveness: 1.0,
         }
     }
+
+    /// Also considers guessing words that can no longer be the answer, when one of them is
+    /// expected to finish the game sooner than the best remaining candidate.
+    pub fn probing(mut self) -> Self {
+        self.probe = true;
+        self
+    }
+
+    /// Stops scoring candidates once none of the rest could beat the best so far by more than a
+    /// factor of `aggressiveness`, assuming each yields as much information as is left. The
+    /// default of 1 never skips a candidate that could win; larger values trade accuracy for
+    /// speed.
+    pub fn pruning(mut self, aggressiveness: f64) -> Self {
+        assert!(aggressiveness >= 1.0, "aggressiveness below 1 prunes nothing extra");
+        self.aggressiveness = aggressiveness;
+        self
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -97,7 +166,28 @@ struct Candidate {
 }
 
 impl Guesser for Sigmoid {
-    fn guess(&mut self, history: &[Guess]) -> String {
+    fn guess(&mut self, history: &[Guess]) -> Result<String, NoCandidates> {
+        Ok(self.guess_until(history, None)?.guess)
+    }
+}
+
+impl AnytimeGuesser for Sigmoid {
+    fn guess_with_budget(
+        &mut self,
+        history: &[Guess],
+        budget: Duration,
+    ) -> Result<BudgetedGuess, NoCandidates> {
+        self.guess_until(history, Instant::now().checked_add(budget))
+    }
+}
+
+impl Sigmoid {
+    /// Guesses, but stops scoring candidates at `deadline`.
+    fn guess_until(
+        &mut self,
+        history: &[Guess],
+        deadline: Option<Instant>,
+    ) -> Result<BudgetedGuess, NoCandidates> {
         if let Some(last) = history.last() {
             if matches!(self.remaining, Cow::Owned(_)) {
                 self.remaining
@@ -114,18 +204,38 @@ impl Guesser for Sigmoid {
             }
         }
         if history.is_empty() {
-            self.patterns = Cow::Borrowed(PATTERNS.get().unwrap());
-            return "tares".to_string();
-        } else {
-            assert!(!self.patterns.is_empty());
+            return Ok(BudgetedGuess {
+                guess: crate::opener().to_string(),
+                scored: 0,
+                candidates: self.remaining.len(),
+                probed: None,
+                complete: true,
+            });
         }
 
         let remaining_p: f64 = self.remaining.iter().map(|&(_, p)| p).sum();
 
+        // No guess can be expected to reveal more than is left to know, nor more than it takes to
+        // tell every mask apart. `remaining` is ordered by weight, so once even that much
+        // information times `p_word` can't beat the best goodness so far, no later word can.
+        let max_entropy = entropy::remaining_entropy(self.remaining.iter().map(|&(_, c)| c))
+            .min((self.remaining.len().min(MAX_MASK_ENUM) as f64).log2());
+
         let mut best: Option<Candidate> = None;
-        let mut i = 0;
-        let stop = (self.remaining.len() / 3).max(20);
+        let mut scored = 0;
+        let mut complete = true;
         for &(word, count) in &*self.remaining {
+            if scored > 0 && deadline.is_some_and(|d| Instant::now() >= d) {
+                complete = false;
+                break;
+            }
+            let p_word = count / remaining_p;
+            if let Some(c) = best {
+                if p_word * max_entropy <= self.aggressiveness * c.goodness {
+                    break;
+                }
+            }
+
             // considering a world where we _did_ guess `word` and got `pattern` as the
             // correctness. now, compute what _then_ is left.
 
@@ -135,7 +245,7 @@ impl Guesser for Sigmoid {
             // pair deterministically produces only one mask.
             let mut totals = [0.0f64; MAX_MASK_ENUM];
             for (candidate, count) in &*self.remaining {
-                let idx = enumerate_mask(&Correctness::compute(candidate, word));
+                let idx = Pattern::compute(candidate, word).index();
                 totals[idx] += count;
             }
 
@@ -143,12 +253,11 @@ impl Guesser for Sigmoid {
                 .into_iter()
                 .filter(|t| *t != 0.0)
                 .map(|p| {
-                    let p_of_this_pattern = p as f64 / remaining_p as f64;
+                    let p_of_this_pattern = p / remaining_p;
                     p_of_this_pattern * p_of_this_pattern.log2()
                 })
                 .sum();
 
-            let p_word = count as f64 / remaining_p as f64;
             let entropy = -sum;
             // TODO: this should be (minimizing):
             // (p_word * (history.len() + 1)) + ((1 - p_word) * estimate_remaining_guesses(remaining_entropy))
@@ -163,12 +272,22 @@ impl Guesser for Sigmoid {
             } else {
                 best = Some(Candidate { word, goodness });
             }
-
-            i += 1;
-            if i >= stop {
-                break;
-            }
+            scored += 1;
+        }
+        let mut guess = best.ok_or(NoCandidates)?.word;
+        let mut probed = None;
+        if self.probe && complete {
+            let (probe, scanned) = entropy::probe_until(&self.remaining, guess, deadline);
+            guess = probe.unwrap_or(guess);
+            complete = scanned == entropy::guess_pool().len();
+            probed = Some(scanned);
         }
-        best.unwrap().word.to_string()
+        Ok(BudgetedGuess {
+            guess: guess.to_string(),
+            scored,
+            candidates: self.remaining.len(),
+            probed,
+            complete,
+        })
     }
 }
@@ -0,0 +1,176 @@
+use crate::fallback::replay;
+use crate::tree::{Node, Tree};
+use crate::{dictionary, Guess, Guesser, NoCandidates, Pattern};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// A precomputed strategy: the word to guess after every sequence of patterns a guesser met,
+/// usually made from its `Tree`.
+///
+/// Tables are stored one guess per line, as the patterns seen so far in the letters `{:#}`
+/// writes, then the word. The opening guess has no patterns before it:
+///
+/// ```text
+/// tares
+/// BYYBB drain
+/// BYYBB GGBBB cigar
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    guesses: HashMap<Vec<Pattern>, String>,
+}
+
+impl Table {
+    /// The table of every guess in `tree`.
+    pub fn from_tree(tree: &Tree) -> Self {
+        fn walk(node: &Node, path: &mut Vec<Pattern>, guesses: &mut HashMap<Vec<Pattern>, String>) {
+            guesses.insert(path.clone(), node.guess.clone());
+            for (&mask, child) in &node.children {
+                path.push(mask);
+                walk(child, path, guesses);
+                path.pop();
+            }
+        }
+
+        let mut guesses = HashMap::new();
+        if let Some(root) = &tree.root {
+            walk(root, &mut Vec::new(), &mut guesses);
+        }
+        Self { guesses }
+    }
+
+    /// Reads a table in the format written by `format`.
+    pub fn parse(table: &str) -> Result<Self, String> {
+        let words: HashSet<&str> = dictionary().iter().map(|&(word, _)| word).collect();
+        let mut guesses = HashMap::new();
+        for (i, line) in table.lines().enumerate() {
+            let mut tokens: Vec<&str> = line.split_whitespace().collect();
+            let Some(word) = tokens.pop() else {
+                continue;
+            };
+            if !words.contains(word) {
+                return Err(format!("line {}: '{}' is not in the dictionary", i + 1, word));
+            }
+            let path = tokens
+                .iter()
+                .map(|t| t.parse())
+                .collect::<Result<Vec<Pattern>, _>>()
+                .map_err(|e| format!("line {}: {}", i + 1, e))?;
+            if guesses.insert(path, word.to_string()).is_some() {
+                return Err(format!("line {}: those patterns already have a guess", i + 1));
+            }
+        }
+        Ok(Self { guesses })
+    }
+
+    /// Writes the table so that `parse` reads it back, shortest paths first.
+    pub fn format(&self) -> String {
+        let mut lines: Vec<_> = self.guesses.iter().collect();
+        lines.sort_by(|a, b| (a.0.len(), a.0).cmp(&(b.0.len(), b.0)));
+        lines
+            .into_iter()
+            .map(|(path, word)| {
+                let mut line = String::new();
+                for mask in path {
+                    line += &format!("{:#} ", mask);
+                }
+                line + word + "\n"
+            })
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.guesses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.guesses.is_empty()
+    }
+
+    /// The guess the table makes after `history`, or `None` if the game has left the table,
+    /// either at a pattern it has no guess for or at a guess it wouldn't have made.
+    pub fn follow(&self, history: &[Guess]) -> Option<&str> {
+        let masks: Vec<Pattern> = history.iter().map(|g| g.mask).collect();
+        for (i, g) in history.iter().enumerate() {
+            if self.guesses.get(&masks[..i])? != &*g.word {
+                return None;
+            }
+        }
+        self.guesses.get(&masks[..]).map(String::as_str)
+    }
+}
+
+/// Looks its guesses up in a `Table`, and once a game leaves the table, hands it over to a live
+/// guesser built with `make`.
+pub struct TableGuesser<G, F> {
+    table: Arc<Table>,
+    make: F,
+    live: Option<G>,
+}
+
+impl<G: Guesser, F: FnMut() -> G> TableGuesser<G, F> {
+    pub fn new(table: Arc<Table>, make: F) -> Self {
+        Self {
+            table,
+            make,
+            live: None,
+        }
+    }
+
+    /// Whether the game has left the table, so that the live guesser is playing.
+    pub fn is_live(&self) -> bool {
+        self.live.is_some()
+    }
+}
+
+impl<G: Guesser, F: FnMut() -> G> Guesser for TableGuesser<G, F> {
+    fn guess(&mut self, history: &[Guess]) -> Result<String, NoCandidates> {
+        if let Some(live) = &mut self.live {
+            return live.guess(history);
+        }
+        if let Some(word) = self.table.follow(history) {
+            return Ok(word.to_string());
+        }
+        let live = self.live.insert((self.make)());
+        replay(live, history)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::Cutoff;
+    use crate::Wordle;
+
+    #[test]
+    fn plays_like_the_tree() {
+        let w = Wordle::new();
+        let answers = ["cigar", "rebut", "sissy", "humph"];
+        let table = Table::from_tree(&w.tree(&answers, Cutoff::new).unwrap());
+        assert_eq!(Table::parse(&table.format()), Ok(table.clone()));
+        assert!(table.format().starts_with("tares\n"));
+
+        let table = Arc::new(table);
+        for answer in answers {
+            let mut g = TableGuesser::new(table.clone(), Cutoff::new);
+            assert_eq!(
+                w.guesses(answer, &mut g),
+                w.guesses(answer, Cutoff::new())
+            );
+            assert!(!g.is_live());
+        }
+
+        // `crane` shows `tares` a pattern the table has no guess for.
+        let mut g = TableGuesser::new(table, Cutoff::new);
+        assert_eq!(w.guesses("crane", &mut g), w.guesses("crane", Cutoff::new()));
+        assert!(g.is_live());
+    }
+
+    #[test]
+    fn bad_tables() {
+        assert!(Table::parse("tares\nBYYBB drain\n").is_ok());
+        assert!(Table::parse("tares\nBYYBB xxxxx\n").is_err());
+        assert!(Table::parse("tares\nBYYB drain\n").is_err());
+        assert!(Table::parse("tares\nBYYBB drain\nBYYBB cigar\n").is_err());
+    }
+}
@@ -1,25 +1,30 @@
-use crate::{Correctness, Guess, Guesser, DICT};
+use crate::prior::{weigh, AnswerPrior, RawFrequency};
+use crate::{Guess, Guesser, NoCandidates, Pattern};
 use once_cell::sync::OnceCell;
 use std::borrow::Cow;
 
-static INITIAL: OnceCell<Vec<(&'static str, usize)>> = OnceCell::new();
+static INITIAL: OnceCell<Vec<(&'static str, f64)>> = OnceCell::new();
 
 pub struct Weight {
-    remaining: Cow<'static, Vec<(&'static str, usize)>>,
+    remaining: Cow<'static, [(&'static str, f64)]>,
+}
+
+impl Default for Weight {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Weight {
     pub fn new() -> Self {
         Self {
-            remaining: Cow::Borrowed(INITIAL.get_or_init(|| {
-                Vec::from_iter(DICT.lines().map(|line| {
-                    let (word, count) = line
-                        .split_once(' ')
-                        .expect("every line is word + space + frequency");
-                    let count: usize = count.parse().expect("every count is a number");
-                    (word, count)
-                }))
-            })),
+            remaining: Cow::Borrowed(INITIAL.get_or_init(|| weigh(&RawFrequency))),
+        }
+    }
+
+    pub fn with_prior<P: AnswerPrior + ?Sized>(prior: &P) -> Self {
+        Self {
+            remaining: Cow::Owned(weigh(prior)),
         }
     }
 }
@@ -31,7 +36,7 @@ struct Candidate {
 }
 
 impl Guesser for Weight {
-    fn guess(&mut self, history: &[Guess]) -> String {
+    fn guess(&mut self, history: &[Guess]) -> Result<String, NoCandidates> {
         if let Some(last) = history.last() {
             if matches!(self.remaining, Cow::Owned(_)) {
                 self.remaining
@@ -48,20 +53,20 @@ impl Guesser for Weight {
             }
         }
         if history.is_empty() {
-            return "tares".to_string();
+            return Ok(crate::opener().to_string());
         }
 
-        let remaining_count: usize = self.remaining.iter().map(|&(_, c)| c).sum();
+        let remaining_count: f64 = self.remaining.iter().map(|&(_, c)| c).sum();
 
         let mut best: Option<Candidate> = None;
         for &(word, count) in &*self.remaining {
             let mut sum = 0.0;
-            let mut self_total_count = 0usize;
+            let mut self_total_count = 0.0;
 
-            for pattern in Correctness::patterns() {
+            for pattern in Pattern::all() {
                 // considering a world where we _did_ guess `word` and got `pattern` as the
                 // correctness. now, compute what _then_ is left.
-                let mut in_pattern_total = 0;
+                let mut in_pattern_total = 0.0;
                 for (candidate, count) in &*self.remaining {
                     let g = Guess {
                         word: Cow::Borrowed(word),
@@ -71,18 +76,21 @@ impl Guesser for Weight {
                         in_pattern_total += count;
                     }
                 }
-                if in_pattern_total == 0 {
+                if in_pattern_total == 0.0 {
                     continue;
                 }
                 self_total_count += in_pattern_total;
-                // TODO: apply sigmoid
-                let p_of_this_pattern = in_pattern_total as f64 / remaining_count as f64;
+                let p_of_this_pattern = in_pattern_total / remaining_count;
                 sum += p_of_this_pattern * p_of_this_pattern.log2();
             }
 
-            debug_assert_eq!(self_total_count, remaining_count, "{}", word);
+            debug_assert!(
+                (self_total_count - remaining_count).abs() <= 1e-9 * remaining_count,
+                "{}",
+                word
+            );
 
-            let p_word = count as f64 / remaining_count as f64;
+            let p_word = count / remaining_count;
             let goodness = p_word * -sum;
             if let Some(c) = best {
                 // Is this one better?
@@ -93,6 +101,6 @@ impl Guesser for Weight {
                 best = Some(Candidate { word, goodness });
             }
         }
-        best.unwrap().word.to_string()
+        best.map(|c| c.word.to_string()).ok_or(NoCandidates)
     }
 }
@@ -0,0 +1,205 @@
+use crate::entropy::{guess_entropy, guess_pool};
+use crate::prior::{weigh, AnswerPrior};
+use crate::validate::{check_hard_mode, HardModeViolation};
+use crate::{Guess, PackedWord, Pattern, Wordle};
+use std::borrow::Cow;
+
+/// A move-by-move review of a game someone played, as seen by a solver that weighs the
+/// dictionary with some prior.
+#[derive(Debug, Clone)]
+pub struct Analysis {
+    pub answer: &'static str,
+    pub moves: Vec<Move>,
+    pub solved: bool,
+    /// The information the guesses were expected to yield as a share of what the best guesses
+    /// were expected to yield, from 0 to 1.
+    pub skill: f64,
+    /// How many more bits the masks yielded than the guesses were expected to yield. Negative
+    /// when the masks were worse than expected.
+    pub luck: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Move {
+    pub guess: &'static str,
+    pub mask: Pattern,
+    /// How many words the prior gives any weight to were still possible before the guess.
+    pub before: usize,
+    /// How many of them are still possible after seeing `mask`.
+    pub after: usize,
+    /// The information, in bits, that the guess was expected to yield.
+    pub expected: f64,
+    /// The information, in bits, that `mask` actually yielded.
+    pub gained: f64,
+    /// The guess expected to yield the most information, and how much.
+    pub best: (&'static str, f64),
+    /// The hard-mode rule the guess broke, if it broke one.
+    pub hard_mode: Option<HardModeViolation>,
+    /// Whether the masks before the guess had already ruled it out as the answer.
+    pub eliminated: bool,
+}
+
+/// Why a game can't be analysed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnalysisError {
+    NotAWord(String),
+    /// The prior gives the answer no weight, so no guess could be expected to find it.
+    Unlikely(&'static str),
+}
+
+impl std::fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnalysisError::NotAWord(word) => write!(f, "'{}' is not in the dictionary", word),
+            AnalysisError::Unlikely(word) => write!(f, "the prior rules out '{}'", word),
+        }
+    }
+}
+
+impl std::error::Error for AnalysisError {}
+
+impl Wordle {
+    /// Reviews the game in which `guesses` were played against `answer`, measuring every guess
+    /// against the one in the guess pool expected to yield the most information with the
+    /// dictionary weighted by `prior`, as `trace` does for guessers.
+    ///
+    /// The first guess is measured against the whole dictionary, which takes seconds.
+    pub fn analyse<P: AnswerPrior + ?Sized>(
+        &self,
+        answer: &str,
+        guesses: &[&str],
+        prior: &P,
+    ) -> Result<Analysis, AnalysisError> {
+        let word = |w: &str| self.word(w).ok_or_else(|| AnalysisError::NotAWord(w.to_string()));
+        let answer = word(answer)?;
+        let guesses = guesses
+            .iter()
+            .map(|&g| word(g))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Words the prior rules out can't be the answer, and add nothing to any entropy.
+        let mut remaining = weigh(prior);
+        remaining.retain(|&(_, w)| w > 0.0);
+        if !remaining.iter().any(|&(w, _)| w == answer) {
+            return Err(AnalysisError::Unlikely(answer));
+        }
+
+        let mut hist: Vec<Guess> = Vec::new();
+        let mut moves = Vec::new();
+        for guess in guesses {
+            let mask = Pattern::compute(answer, guess);
+            let answers: Vec<_> = remaining
+                .iter()
+                .map(|&(word, w)| (PackedWord::new(word), w))
+                .collect();
+            let total: f64 = remaining.iter().map(|&(_, w)| w).sum();
+            let expected = guess_entropy(PackedWord::new(guess), &answers, total);
+            let best = guess_pool()
+                .iter()
+                .map(|&(word, packed)| (word, guess_entropy(packed, &answers, total)))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .expect("the guess pool isn't empty");
+            let before = remaining.len();
+            let hard_mode = check_hard_mode(&hist, guess).err();
+            let eliminated = !hist.iter().all(|g| g.matches(guess));
+
+            let g = Guess {
+                word: Cow::Borrowed(guess),
+                mask,
+            };
+            remaining.retain(|&(word, _)| g.matches(word));
+            let left: f64 = remaining.iter().map(|&(_, w)| w).sum();
+            moves.push(Move {
+                guess,
+                mask,
+                before,
+                after: remaining.len(),
+                expected,
+                gained: (total / left).log2(),
+                best,
+                hard_mode,
+                eliminated,
+            });
+            hist.push(g);
+            if mask.is_win() {
+                break;
+            }
+        }
+
+        let expected: f64 = moves.iter().map(|m| m.expected).sum();
+        let best: f64 = moves.iter().map(|m| m.best.1).sum();
+        let gained: f64 = moves.iter().map(|m| m.gained).sum();
+        Ok(Analysis {
+            answer,
+            solved: moves.last().is_some_and(|m| m.mask.is_win()),
+            // With nothing left to learn, every guess was as good as the best.
+            skill: if best > 0.0 { expected / best } else { 1.0 },
+            luck: gained - expected,
+            moves,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Only a handful of words are possible, so that the whole guess pool is quick to search.
+    struct Few;
+
+    impl AnswerPrior for Few {
+        fn weight(&self, word: &str, _: usize, _: usize) -> f64 {
+            match word {
+                "fight" | "light" | "might" | "night" | "right" | "sight" | "tight" => 1.0,
+                "cigar" => 2.0,
+                _ => 0.0,
+            }
+        }
+    }
+
+    #[test]
+    fn reviews_a_game() {
+        let w = Wordle::new();
+        let analysis = w
+            .analyse("night", &["cigar", "sight", "night"], &Few)
+            .unwrap();
+        assert!(analysis.solved);
+        let [cigar, sight, night] = &analysis.moves[..] else {
+            panic!("{:?}", analysis.moves);
+        };
+        assert_eq!((cigar.before, cigar.after), (8, 6));
+        assert_eq!((sight.before, sight.after), (6, 5));
+        assert_eq!((night.before, night.after), (5, 1));
+        assert!(cigar.best.1 > cigar.expected);
+        assert!(sight.best.1 > sight.expected);
+        assert!(cigar.hard_mode.is_none() && !cigar.eliminated);
+        assert!(!sight.eliminated);
+        assert!(0.0 < analysis.skill && analysis.skill < 1.0);
+
+        // Every bit gained narrows the eight words down to the answer.
+        let gained: f64 = analysis.moves.iter().map(|m| m.gained).sum();
+        assert!((gained - 9f64.log2()).abs() < 1e-9, "{}", gained);
+        let expected: f64 = analysis.moves.iter().map(|m| m.expected).sum();
+        assert!((analysis.luck - (gained - expected)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn flags_careless_guesses() {
+        let w = Wordle::new();
+        let analysis = w.analyse("night", &["sight", "cigar", "fight"], &Few).unwrap();
+        assert!(!analysis.solved);
+        let careless = &analysis.moves[1];
+        assert!(careless.eliminated);
+        assert!(careless.hard_mode.is_some());
+        assert!(careless.best.1 > careless.expected);
+
+        assert_eq!(
+            w.analyse("night", &["xxxxx"], &Few).unwrap_err(),
+            AnalysisError::NotAWord("xxxxx".to_string())
+        );
+        assert_eq!(
+            w.analyse("tares", &["night"], &Few).unwrap_err(),
+            AnalysisError::Unlikely("tares")
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A day of the Gregorian calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    /// Days since 1970-01-01.
+    days: i64,
+}
+
+impl Date {
+    /// The day of the first puzzle of the official answer list, 2021-06-19.
+    pub const FIRST_PUZZLE: Date = Date { days: 18797 };
+
+    /// The date, if `month` and `day` are a day of `year`.
+    pub fn new(year: i64, month: u32, day: u32) -> Option<Self> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        // Counting years from March puts the leap day at the end of each one.
+        let y = if month <= 2 { year - 1 } else { year };
+        let era = y.div_euclid(400);
+        let year_of_era = y.rem_euclid(400);
+        let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100
+            + day_of_year as i64;
+        Some(Self {
+            days: era * 146097 + day_of_era - 719468,
+        })
+    }
+
+    pub fn from_days(days: i64) -> Self {
+        Self { days }
+    }
+
+    /// The day it is now in UTC.
+    pub fn today() -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("the clock is after 1970");
+        Self::from_days((now.as_secs() / 86400) as i64)
+    }
+
+    /// Days since 1970-01-01.
+    pub fn days(self) -> i64 {
+        self.days
+    }
+
+    /// The year, month and day of the month.
+    pub fn ymd(self) -> (i64, u32, u32) {
+        let z = self.days + 719468;
+        let era = z.div_euclid(146097);
+        let day_of_era = z.rem_euclid(146097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+        let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        (year, month, day)
+    }
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Writes the date as `YYYY-MM-DD`.
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = self.ymd();
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDateError(String);
+
+impl fmt::Display for ParseDateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' is not a date: expected YYYY-MM-DD", self.0)
+    }
+}
+
+impl std::error::Error for ParseDateError {}
+
+/// The year and month of `YYYY-MM`, and the day too if there is a third number.
+fn parse_ymd(s: &str) -> Option<(i64, u32, Option<u32>)> {
+    let mut parts = s.trim().split('-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let day = parts.next().map(str::parse).transpose().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((year, month, day))
+}
+
+impl FromStr for Date {
+    type Err = ParseDateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match parse_ymd(s) {
+            Some((year, month, Some(day))) => Date::new(year, month, day),
+            _ => None,
+        }
+        .ok_or_else(|| ParseDateError(s.to_string()))
+    }
+}
+
+/// Every day from `first` to `last`, both included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    pub first: Date,
+    pub last: Date,
+}
+
+impl DateRange {
+    /// Every day of `month` in `year`.
+    pub fn month(year: i64, month: u32) -> Option<Self> {
+        Some(Self {
+            first: Date::new(year, month, 1)?,
+            last: Date::new(year, month, days_in_month(year, month))?,
+        })
+    }
+
+    pub fn days(self) -> impl Iterator<Item = Date> {
+        (self.first.days..=self.last.days).map(Date::from_days)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDateRangeError(String);
+
+impl fmt::Display for ParseDateRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "'{}' is not a range of days: expected YYYY-MM, YYYY-MM-DD or FIRST..LAST",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseDateRangeError {}
+
+/// Reads a whole month as `YYYY-MM`, a single day, or the first and last days joined by `..`.
+impl FromStr for DateRange {
+    type Err = ParseDateRangeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let range = match s.split_once("..") {
+            Some((first, last)) => first.parse().ok().zip(last.parse().ok()).map(
+                |(first, last)| DateRange { first, last },
+            ),
+            None => match parse_ymd(s) {
+                Some((year, month, None)) => DateRange::month(year, month),
+                Some((year, month, Some(day))) => Date::new(year, month, day)
+                    .map(|day| DateRange {
+                        first: day,
+                        last: day,
+                    }),
+                None => None,
+            },
+        };
+        range
+            .filter(|r| r.first <= r.last)
+            .ok_or_else(|| ParseDateRangeError(s.to_string()))
+    }
+}
+
+/// The puzzles of an answer list played one a day in order from `epoch`, starting over from
+/// the top when the list runs out.
+#[derive(Debug, Clone)]
+pub struct Calendar {
+    epoch: Date,
+    answers: Vec<&'static str>,
+}
+
+impl Calendar {
+    /// Panics if `answers` is empty.
+    pub fn new(epoch: Date, answers: Vec<&'static str>) -> Self {
+        assert!(!answers.is_empty(), "a calendar needs answers");
+        Self { epoch, answers }
+    }
+
+    pub fn epoch(&self) -> Date {
+        self.epoch
+    }
+
+    /// The puzzle number of `date`, counting from 0 at the epoch, or `None` before it.
+    pub fn number(&self, date: Date) -> Option<usize> {
+        usize::try_from(date.days - self.epoch.days).ok()
+    }
+
+    /// Where the answer of `date` is in the list, or `None` before the epoch.
+    pub fn index(&self, date: Date) -> Option<usize> {
+        self.number(date).map(|n| n % self.answers.len())
+    }
+
+    pub fn answer(&self, date: Date) -> Option<&'static str> {
+        self.index(date).map(|i| self.answers[i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates() {
+        assert_eq!(Date::new(1970, 1, 1), Some(Date::from_days(0)));
+        assert_eq!(Date::new(2021, 6, 19), Some(Date::FIRST_PUZZLE));
+        assert_eq!(Date::FIRST_PUZZLE.to_string(), "2021-06-19");
+        for days in [-1, 0, 59, 10957, 18797, 20744, 110_000] {
+            let date = Date::from_days(days);
+            assert_eq!(date.to_string().parse(), Ok(date), "{}", date);
+        }
+
+        assert_eq!("2024-02-29".parse::<Date>().unwrap().days(), 19782);
+        for bad in ["2023-02-29", "2026-13-01", "2026-10", "2026-10-18-1", "today"] {
+            assert!(bad.parse::<Date>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn ranges() {
+        let october: DateRange = "2026-10".parse().unwrap();
+        assert_eq!(october.days().count(), 31);
+        assert_eq!(october.last.to_string(), "2026-10-31");
+        let range: DateRange = "2024-02-27..2024-03-01".parse().unwrap();
+        assert_eq!(range.days().count(), 4);
+        assert_eq!("2026-10-18".parse::<DateRange>().unwrap().days().count(), 1);
+        assert!("2026-10-05..2026-10-01".parse::<DateRange>().is_err());
+        assert!("2026-10..2026-11".parse::<DateRange>().is_err());
+    }
+
+    #[test]
+    fn daily_answers() {
+        let answers: Vec<_> = include_str!("../answers.txt").split_whitespace().collect();
+        let len = answers.len();
+        let calendar = Calendar::new(Date::FIRST_PUZZLE, answers);
+        let day = |s: &str| s.parse::<Date>().unwrap();
+        assert_eq!(calendar.answer(day("2021-06-19")), Some("cigar"));
+        assert_eq!(calendar.answer(day("2021-06-20")), Some("rebut"));
+        assert_eq!(calendar.answer(day("2021-06-18")), None);
+        let again = Date::from_days(Date::FIRST_PUZZLE.days() + len as i64);
+        assert_eq!(calendar.number(again), Some(len));
+        assert_eq!(calendar.answer(again), Some("cigar"));
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{dictionary, PackedWord};
+use once_cell::sync::OnceCell;
+use std::collections::HashMap;
+use std::time::Instant;
+
+static GUESS_POOL: OnceCell<Vec<(&'static str, PackedWord)>> = OnceCell::new();
+
+/// Every word that may be guessed, whether or not it can still be the answer.
+pub fn guess_pool() -> &'static [(&'static str, PackedWord)] {
+    GUESS_POOL.get_or_init(|| {
+        dictionary()
+            .iter()
+            .map(|&(word, _)| (word, PackedWord::new(word)))
+            .collect()
+    })
+}
+
+/// The entropy, in bits, of which of `weights` is the answer.
+pub fn remaining_entropy(weights: impl IntoIterator<Item = f64> + Clone) -> f64 {
+    let total: f64 = weights.clone().into_iter().sum();
+    -weights
+        .into_iter()
+        .filter(|&w| w > 0.0)
+        .map(|w| {
+            let p = w / total;
+            p * p.log2()
+        })
+        .sum::<f64>()
+}
+
+/// The expected information, in bits, from guessing `guess` when the answer is one of `answers`.
+pub fn guess_entropy(guess: PackedWord, answers: &[(PackedWord, f64)], total: f64) -> f64 {
+    -guess
+        .pattern_weights(answers)
+        .into_iter()
+        .filter(|&t| t > 0.0)
+        .map(|t| {
+            let p = t / total;
+            p * p.log2()
+        })
+        .sum::<f64>()
+}
+
+/// Roughly how many more guesses it takes, including the next one, to find the answer when there
+/// are `bits` of uncertainty left about it.
+///
+/// This is a least-squares fit of `a + b * log2(bits + 1)` to every turn of `Sigmoid` playing a
+/// third of `answers.txt`.
+pub fn estimate_remaining_guesses(bits: f64) -> f64 {
+    1.03 + 0.69 * (bits + 1.0).log2()
+}
+
+/// The expected number of guesses to finish the game, including this one, when guessing a word
+/// that is the answer with probability `p_word` and is expected to yield `entropy` of the
+/// `remaining_entropy` bits left.
+pub fn expected_guesses(p_word: f64, entropy: f64, remaining_entropy: f64) -> f64 {
+    let left = (remaining_entropy - entropy).max(0.0);
+    p_word + (1.0 - p_word) * (1.0 + estimate_remaining_guesses(left))
+}
+
+/// Looks for a word in the guess pool that is expected to finish the game sooner than
+/// `candidate`, usually one that can't be the answer itself but splits `remaining` better.
+///
+/// `remaining` is the answer pool: the words that are still possible, with their weights.
+pub fn probe(remaining: &[(&'static str, f64)], candidate: &str) -> Option<&'static str> {
+    probe_until(remaining, candidate, None).0
+}
+
+/// Like `probe`, but gives up scanning the guess pool at `deadline`, returning the best probe
+/// found so far and how many words of the pool were scanned. All of them count as scanned when
+/// there is nothing to look for.
+pub fn probe_until(
+    remaining: &[(&'static str, f64)],
+    candidate: &str,
+    deadline: Option<Instant>,
+) -> (Option<&'static str>, usize) {
+    // With two or fewer words left, guessing one of them is always at least as good.
+    if remaining.len() <= 2 {
+        return (None, guess_pool().len());
+    }
+
+    let total: f64 = remaining.iter().map(|&(_, w)| w).sum();
+    let answers: Vec<_> = remaining
+        .iter()
+        .map(|&(word, w)| (PackedWord::new(word), w))
+        .collect();
+    let p_word: HashMap<_, _> = remaining.iter().map(|&(word, w)| (word, w / total)).collect();
+    let entropy = remaining_entropy(remaining.iter().map(|&(_, w)| w));
+    let score = |packed: PackedWord, word: &str| {
+        let p = p_word.get(word).copied().unwrap_or(0.0);
+        expected_guesses(p, guess_entropy(packed, &answers, total), entropy)
+    };
+
+    let to_beat = score(PackedWord::new(candidate), candidate);
+    let mut best: Option<(&'static str, f64)> = None;
+    for (i, &(word, packed)) in guess_pool().iter().enumerate() {
+        if deadline.is_some_and(|d| Instant::now() >= d) {
+            return (best.map(|(word, _)| word), i);
+        }
+        let s = score(packed, word);
+        if s < to_beat && best.is_none_or(|(_, b)| s < b) {
+            best = Some((word, s));
+        }
+    }
+    (best.map(|(word, _)| word), guess_pool().len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uniform_entropy() {
+        assert_eq!(remaining_entropy([1.0; 8]), 3.0);
+        assert_eq!(remaining_entropy([5.0]), 0.0);
+    }
+
+    #[test]
+    fn splits_the_ight_family() {
+        // Guessing these one at a time can take six guesses; a word that tests several of the
+        // first letters at once is expected to do better.
+        let remaining: Vec<_> = ["fight", "light", "might", "night", "right", "sight", "tight"]
+            .into_iter()
+            .map(|w| (w, 1.0))
+            .collect();
+        let probe = probe(&remaining, "fight").expect("a probe beats guessing one at a time");
+        assert!(!remaining.iter().any(|&(w, _)| w == probe), "{}", probe);
+    }
+
+    #[test]
+    fn no_probe_for_two_words() {
+        assert_eq!(probe(&[("fight", 1.0), ("light", 1.0)], "fight"), None);
+    }
+}
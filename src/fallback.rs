@@ -0,0 +1,114 @@
+use crate::validate::check_history;
+use crate::{Guess, Guesser, NoCandidates};
+use std::borrow::Cow;
+
+/// Wraps a guesser so that a history no dictionary word fits, usually from a mistyped mask, drops
+/// guesses until it does instead of giving up.
+///
+/// The guesses considered least trustworthy are the ones that first contradict those before them,
+/// so each time the guesser runs out of candidates the wrapper drops the entry that
+/// `check_history` reports. Guessers already consider the whole dictionary as answers, so there
+/// is no larger word list to fall back to. Guessers filter their candidates as the history grows,
+/// so after dropping an entry the wrapper builds a fresh one with `make` and replays what's left.
+pub struct Relaxed<G, F> {
+    make: F,
+    guesser: G,
+    dropped: Vec<usize>,
+}
+
+impl<G: Guesser, F: FnMut() -> G> Relaxed<G, F> {
+    pub fn new(mut make: F) -> Self {
+        let guesser = make();
+        Self {
+            make,
+            guesser,
+            dropped: Vec::new(),
+        }
+    }
+
+    /// The history entries that have been dropped, in the order they were dropped.
+    pub fn dropped(&self) -> &[usize] {
+        &self.dropped
+    }
+
+    /// `hist` without the dropped entries, and the index of each kept entry in `hist`.
+    fn kept<'a>(&self, hist: &'a [Guess]) -> (Vec<Guess<'a>>, Vec<usize>) {
+        hist.iter()
+            .enumerate()
+            .filter(|(i, _)| !self.dropped.contains(i))
+            .map(|(i, g)| {
+                let g = Guess {
+                    word: Cow::Borrowed(&*g.word),
+                    mask: g.mask,
+                };
+                (g, i)
+            })
+            .unzip()
+    }
+}
+
+impl<G: Guesser, F: FnMut() -> G> Guesser for Relaxed<G, F> {
+    fn guess(&mut self, hist: &[Guess]) -> Result<String, NoCandidates> {
+        let (kept, _) = self.kept(hist);
+        if let Ok(guess) = self.guesser.guess(&kept) {
+            return Ok(guess);
+        }
+
+        let mut relaxed = false;
+        loop {
+            let (kept, index) = self.kept(hist);
+            match check_history(&kept) {
+                Ok(()) => break,
+                Err(contradiction) => self.dropped.push(index[contradiction.entry()]),
+            }
+            relaxed = true;
+        }
+        if !relaxed {
+            return Err(NoCandidates);
+        }
+
+        let (kept, _) = self.kept(hist);
+        self.guesser = (self.make)();
+        replay(&mut self.guesser, &kept)
+    }
+}
+
+/// Catches a fresh guesser up on `hist` one guess at a time, since guessers only narrow their
+/// candidates by the latest mask, and returns its next guess.
+pub(crate) fn replay<G: Guesser>(guesser: &mut G, hist: &[Guess]) -> Result<String, NoCandidates> {
+    for n in 0..hist.len() {
+        guesser.guess(&hist[..n])?;
+    }
+    guesser.guess(hist)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::Cutoff;
+    use crate::Pattern;
+
+    #[test]
+    fn drops_a_contradicting_guess() {
+        // `cigar` all grey rules out the answer `tares` all but green says is left.
+        let mut hist = vec![Guess {
+            word: Cow::Borrowed("cigar"),
+            mask: "BBBBB".parse().unwrap(),
+        }];
+        let mut plain = Cutoff::new();
+        let mut relaxed = Relaxed::new(Cutoff::new);
+        for g in [&mut plain as &mut dyn Guesser, &mut relaxed] {
+            g.guess(&[]).unwrap();
+            g.guess(&hist).unwrap();
+        }
+
+        hist.push(Guess {
+            word: Cow::Borrowed("tares"),
+            mask: "GGGGB".parse().unwrap(),
+        });
+        assert_eq!(plain.guess(&hist), Err(NoCandidates));
+        let guess = relaxed.guess(&hist).unwrap();
+        assert_eq!(relaxed.dropped(), [1]);
+        assert_eq!(Pattern::compute(&guess, "cigar"), hist[0].mask);
+    }
+}
@@ -0,0 +1,123 @@
+use crate::algorithms::{Sigmoid, SigmoidParams};
+use crate::{dictionary, Wordle};
+use std::collections::HashSet;
+
+// Keeps `ln` finite for words the curve puts at exactly 0 or 1.
+const EPSILON: f64 = 1e-12;
+
+/// The outcome of `fit_sigmoid`.
+#[derive(Debug, Clone, Copy)]
+pub struct Fit {
+    pub params: SigmoidParams,
+    pub score: f64,
+    pub evaluations: usize,
+}
+
+/// Searches `k` and `x0` around `start` for the parameters that maximise `score`.
+///
+/// Each round evaluates a `steps` × `steps` grid spaced evenly over `log10(k)` and `log10(x0)`,
+/// starting one decade either side of `start`, and re-centres on the best point. The span is
+/// halved after every round whose best point is inside the grid; if it is on the edge, the
+/// optimum may lie beyond it, so the next round searches the same span around it instead.
+/// `l` is left as it is, since it only scales every weight equally.
+pub fn fit_sigmoid(
+    start: SigmoidParams,
+    steps: usize,
+    rounds: usize,
+    mut score: impl FnMut(SigmoidParams) -> f64,
+) -> Fit {
+    assert!(steps >= 2, "a grid needs at least two steps per axis");
+    let mut best = Fit {
+        params: start,
+        score: score(start),
+        evaluations: 1,
+    };
+    let mut span = 1.0;
+    for _ in 0..rounds {
+        let (k, x0) = (best.params.k.log10(), best.params.x0.log10());
+        let mut on_edge = false;
+        for i in 0..steps {
+            for j in 0..steps {
+                let params = SigmoidParams {
+                    k: 10f64.powf(k - span + 2.0 * span * i as f64 / (steps - 1) as f64),
+                    x0: 10f64.powf(x0 - span + 2.0 * span * j as f64 / (steps - 1) as f64),
+                    ..best.params
+                };
+                let s = score(params);
+                best.evaluations += 1;
+                if s > best.score {
+                    best.params = params;
+                    best.score = s;
+                    on_edge = i == 0 || j == 0 || i == steps - 1 || j == steps - 1;
+                }
+            }
+        }
+        if !on_edge {
+            span /= 2.0;
+        }
+    }
+    best
+}
+
+/// How well `params` separates `answers` from the rest of the dictionary: the mean
+/// log-likelihood of each word's membership in `answers` when the curve is read as the
+/// probability that a word is an answer. Higher is better, and 0 is a perfect split.
+pub fn separation(params: SigmoidParams, answers: &HashSet<&str>) -> f64 {
+    let dict = dictionary();
+    let sum: usize = dict.iter().map(|&(_, count)| count).sum();
+    let total: f64 = dict
+        .iter()
+        .map(|&(word, count)| {
+            let p = (params.apply(count as f64 / sum as f64) / params.l).clamp(EPSILON, 1.0 - EPSILON);
+            if answers.contains(word) {
+                p.ln()
+            } else {
+                (1.0 - p).ln()
+            }
+        })
+        .sum();
+    total / dict.len() as f64
+}
+
+/// The average number of guesses `Sigmoid` built with `params` needs to solve `answers`, with
+/// every failed game counted as 32 guesses.
+pub fn benchmark(params: SigmoidParams, answers: &[&'static str]) -> f64 {
+    let w = Wordle::new();
+    let total: usize = answers
+        .iter()
+        .map(|&answer| w.play(answer, Sigmoid::with_params(params)).unwrap_or(32))
+        .sum();
+    total as f64 / answers.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_peak() {
+        let target = SigmoidParams {
+            k: 1e7,
+            x0: 2e-6,
+            ..SigmoidParams::default()
+        };
+        let fit = fit_sigmoid(SigmoidParams::default(), 5, 6, |p| {
+            -((p.k / target.k).log10().powi(2) + (p.x0 / target.x0).log10().powi(2))
+        });
+        assert!((fit.params.k / target.k).log10().abs() < 0.05, "{:?}", fit);
+        assert!((fit.params.x0 / target.x0).log10().abs() < 0.05, "{:?}", fit);
+        assert_eq!(fit.evaluations, 1 + 6 * 25);
+    }
+
+    #[test]
+    fn separation_beats_a_flat_curve() {
+        let answers: HashSet<_> = include_str!("../answers.txt").split_whitespace().collect();
+        let flat = SigmoidParams {
+            k: 0.0,
+            ..SigmoidParams::default()
+        };
+        let fit = fit_sigmoid(SigmoidParams::default(), 5, 8, |p| separation(p, &answers));
+        assert!(fit.score > separation(flat, &answers), "{:?}", fit);
+        assert!(fit.score >= separation(SigmoidParams::default(), &answers));
+    }
+}
@@ -0,0 +1,106 @@
+use std::fmt;
+
+/// One game of a golden corpus: an answer and every guess made to find it.
+///
+/// Corpora are stored one game per line, as the answer, a colon, and the guesses separated by
+/// spaces. A game was lost if its last guess isn't the answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub answer: String,
+    pub guesses: Vec<String>,
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.answer, self.guesses.join(" "))
+    }
+}
+
+/// Reads a corpus in the format written by `Game`'s `Display` impl.
+pub fn parse(corpus: &str) -> Result<Vec<Game>, String> {
+    corpus
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let (answer, guesses) = line
+                .split_once(':')
+                .ok_or_else(|| format!("line {}: expected 'answer: guesses...'", i + 1))?;
+            Ok(Game {
+                answer: answer.trim().to_string(),
+                guesses: guesses.split_whitespace().map(String::from).collect(),
+            })
+        })
+        .collect()
+}
+
+/// Writes a corpus that `parse` reads back.
+pub fn format(games: &[Game]) -> String {
+    games.iter().map(|g| format!("{}\n", g)).collect()
+}
+
+/// Describes every game that differs between `expected` and `actual`, or `None` if they match.
+/// The games are compared in order.
+pub fn diff(expected: &[Game], actual: &[Game]) -> Option<String> {
+    let mut out = String::new();
+    let mut changed = 0;
+    for (e, a) in expected.iter().zip(actual) {
+        if e != a {
+            changed += 1;
+            out += &format!("- {}\n+ {}\n", e, a);
+        }
+    }
+    for e in expected.iter().skip(actual.len()) {
+        changed += 1;
+        out += &format!("- {}\n", e);
+    }
+    for a in actual.iter().skip(expected.len()) {
+        changed += 1;
+        out += &format!("+ {}\n", a);
+    }
+    if changed == 0 {
+        return None;
+    }
+    Some(format!("{} of {} games changed:\n{}", changed, expected.len(), out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(answer: &str, guesses: &[&str]) -> Game {
+        Game {
+            answer: answer.to_string(),
+            guesses: guesses.iter().map(|g| g.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let games = [
+            game("cigar", &["tares", "drain", "cigar"]),
+            game("rebut", &["tares", "rebut"]),
+        ];
+        let corpus = format(&games);
+        assert_eq!(corpus, "cigar: tares drain cigar\nrebut: tares rebut\n");
+        assert_eq!(parse(&corpus).unwrap(), games);
+        assert!(parse("cigar tares").is_err());
+    }
+
+    #[test]
+    fn shows_changed_games() {
+        let expected = [
+            game("cigar", &["tares", "drain", "cigar"]),
+            game("rebut", &["tares", "rebut"]),
+        ];
+        let actual = [
+            game("cigar", &["tares", "drain", "cigar"]),
+            game("rebut", &["tares", "brute", "rebut"]),
+        ];
+        assert_eq!(diff(&expected, &expected), None);
+        assert_eq!(
+            diff(&expected, &actual).unwrap(),
+            "1 of 2 games changed:\n- rebut: tares rebut\n+ rebut: tares brute rebut\n"
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use crate::serve::{session, GameSession};
+use crate::{tiles, Implementation, Tuning, GAMES};
+use logus::entropy::guess_entropy;
+use logus::prior::weigh;
+use logus::validate::check_hard_mode;
+use logus::{Correctness, Guess, PackedWord, Pattern, Wordle};
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::borrow::Cow;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How many guesses a player gets.
+const ROUNDS: usize = 6;
+
+const KEYBOARD: [&str; 3] = ["qwertyuiop", "asdfghjkl", "zxcvbnm"];
+
+/// A game played by a person.
+struct Game {
+    answer: &'static str,
+    hard: bool,
+    history: Vec<Guess<'static>>,
+    /// The best mark each letter has had, by letter from `a`.
+    keys: [Option<Correctness>; 26],
+}
+
+impl Game {
+    fn new(answer: &'static str, hard: bool) -> Self {
+        Self {
+            answer,
+            hard,
+            history: Vec::new(),
+            keys: [None; 26],
+        }
+    }
+
+    /// Plays `input` as the next guess and returns its mask, or says why it can't be played.
+    fn submit(&mut self, w: &Wordle, input: &str) -> Result<Pattern, String> {
+        let input = input.trim().to_lowercase();
+        if input.len() != 5 || !input.bytes().all(|b| b.is_ascii_lowercase()) {
+            return Err("guesses are five letters".to_string());
+        }
+        let word = w
+            .word(&input)
+            .ok_or_else(|| format!("'{}' is not in the word list", input))?;
+        if self.hard {
+            check_hard_mode(&self.history, word).map_err(|e| e.to_string())?;
+        }
+
+        let mask = Pattern::compute(self.answer, word);
+        for (letter, c) in word.bytes().zip(mask.to_array()) {
+            // Greens sort before yellows, and yellows before greys.
+            let key = &mut self.keys[(letter - b'a') as usize];
+            *key = Some(key.map_or(c, |k| k.min(c)));
+        }
+        self.history.push(Guess {
+            word: Cow::Borrowed(word),
+            mask,
+        });
+        Ok(mask)
+    }
+
+    fn won(&self) -> bool {
+        self.history.last().is_some_and(|g| g.mask.is_win())
+    }
+
+    fn over(&self) -> bool {
+        self.won() || self.history.len() == ROUNDS
+    }
+
+    /// The keyboard with every letter marked by what is known about it. Without colour, letters
+    /// known not to be in the answer are dots and those in the right place somewhere are upper
+    /// case.
+    fn keyboard(&self, colour: bool) -> String {
+        let mut out = String::new();
+        for (indent, row) in KEYBOARD.iter().enumerate() {
+            out += &" ".repeat(indent);
+            for letter in row.chars() {
+                let key = self.keys[(letter as u8 - b'a') as usize];
+                let shown = match key {
+                    None => letter.to_string(),
+                    Some(c) if colour => {
+                        let style = match c {
+                            Correctness::Correct => "30;42",
+                            Correctness::Misplaced => "30;43",
+                            Correctness::Incorrect => "90",
+                        };
+                        format!("\x1b[{}m{}\x1b[0m", style, letter)
+                    }
+                    Some(Correctness::Correct) => letter.to_ascii_uppercase().to_string(),
+                    Some(Correctness::Misplaced) => letter.to_string(),
+                    Some(Correctness::Incorrect) => ".".to_string(),
+                };
+                out += &shown;
+                out.push(' ');
+            }
+            out = out.trim_end().to_string();
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Follows a person's game with a solver, to say how it rates each guess.
+struct Rater {
+    implementation: Implementation,
+    session: GameSession,
+    remaining: Vec<(&'static str, f64)>,
+}
+
+impl Rater {
+    fn new(implementation: Implementation) -> Self {
+        let tuning = Tuning::default();
+        Self {
+            implementation,
+            session: session(implementation, &tuning),
+            remaining: weigh(&*implementation.prior().build()),
+        }
+    }
+
+    /// How the solver rates guessing `word` and seeing `mask`, against what it would have played.
+    fn rate(&mut self, word: &str, mask: Pattern) -> String {
+        let pick = self.session.guess();
+        let answers: Vec<_> = self
+            .remaining
+            .iter()
+            .map(|&(w, p)| (PackedWord::new(w), p))
+            .collect();
+        let total: f64 = self.remaining.iter().map(|&(_, p)| p).sum();
+        let expected = guess_entropy(PackedWord::new(word), &answers, total);
+
+        let g = Guess {
+            word: Cow::Borrowed(word),
+            mask,
+        };
+        self.remaining.retain(|&(w, _)| g.matches(w));
+        let left: f64 = self.remaining.iter().map(|&(_, p)| p).sum();
+        let gained = (total / left).log2();
+        self.session
+            .feedback(word, mask)
+            .expect("the game only takes dictionary words and true masks");
+
+        let name = self.implementation.name();
+        let rating = format!("{:.2} bits expected, {:.2} gained", expected, gained);
+        match pick {
+            Ok(pick) if pick == word => format!("{} would play that too: {}", name, rating),
+            Ok(pick) => format!(
+                "{}: {}; it would play {} for {:.2} bits expected",
+                name,
+                rating,
+                pick,
+                guess_entropy(PackedWord::new(&pick), &answers, total)
+            ),
+            Err(e) => format!("{}: {}; {}", name, rating, e),
+        }
+    }
+}
+
+/// Plays a game with a person at the terminal, against `answer` or else a random one from the
+/// answer list.
+pub fn play_human(
+    answer: Option<&'static str>,
+    seed: Option<u64>,
+    hard: bool,
+    rate: Option<Implementation>,
+) {
+    let w = Wordle::new();
+    let answer = answer.unwrap_or_else(|| {
+        let seed = seed.unwrap_or_else(|| {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("the clock is after 1970");
+            now.as_nanos() as u64
+        });
+        let answers: Vec<&'static str> = GAMES.split_whitespace().collect();
+        answers
+            .choose(&mut ChaCha8Rng::seed_from_u64(seed))
+            .expect("the answer list isn't empty")
+    });
+
+    let mut game = Game::new(answer, hard);
+    let mut rater = rate.map(Rater::new);
+    let colour = io::stdout().is_terminal();
+    let mut lines = io::stdin().lock().lines();
+    let mode = if hard { " in hard mode" } else { "" };
+    println!("guess the five-letter word in {} tries{}", ROUNDS, mode);
+
+    while !game.over() {
+        print!("guess {}/{}: ", game.history.len() + 1, ROUNDS);
+        io::stdout().flush().ok();
+        let Some(Ok(line)) = lines.next() else {
+            println!();
+            break;
+        };
+        let mask = match game.submit(&w, &line) {
+            Ok(mask) => mask,
+            Err(e) => {
+                println!("{}", e);
+                continue;
+            }
+        };
+        let word = &game.history.last().expect("a guess was just played").word;
+        let rating = rater.as_mut().map(|r| r.rate(word, mask));
+
+        println!();
+        for g in &game.history {
+            println!("  {}", tiles(&g.word, g.mask, colour));
+        }
+        if let Some(rating) = rating {
+            println!("{}", rating);
+        }
+        println!();
+        if !game.over() {
+            print!("{}", game.keyboard(colour));
+        }
+    }
+
+    if game.won() {
+        println!("solved in {}/{}", game.history.len(), ROUNDS);
+    } else {
+        println!("the answer was {}", answer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_guesses() {
+        let w = Wordle::new();
+        let mut game = Game::new("cigar", true);
+        assert!(game.submit(&w, "tare").is_err());
+        assert!(game.submit(&w, "xxxxx").is_err());
+        assert_eq!(game.submit(&w, " TARES\n"), Ok("BYYBB".parse().unwrap()));
+        assert_eq!(
+            game.submit(&w, "crone"),
+            Err("guess must contain A".to_string())
+        );
+        assert_eq!(game.history.len(), 1);
+        assert!(game.submit(&w, "cigar").is_ok());
+        assert!(game.won() && game.over());
+    }
+
+    #[test]
+    fn keyboard_keeps_the_best_mark() {
+        let w = Wordle::new();
+        let mut game = Game::new("cigar", false);
+        // `a` and `r` are misplaced in `tares`, then in the right place at the end of `radar`,
+        // where their other copies are grey.
+        game.submit(&w, "tares").unwrap();
+        game.submit(&w, "radar").unwrap();
+        let keyboard = game.keyboard(false);
+        let mut rows = keyboard.lines();
+        assert_eq!(rows.next(), Some("q w . R . y u i o p"));
+        assert_eq!(rows.next(), Some(" A . . f g h j k l"));
+        assert_eq!(rows.next(), Some("  z x c v b n m"));
+    }
+}
@@ -1,36 +1,144 @@
-use std::{borrow::Cow, collections::HashSet};
+use once_cell::sync::OnceCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{borrow::Cow, collections::{HashMap, HashSet}, time::Duration};
 
 pub mod algorithms;
+pub mod analysis;
+pub mod daily;
+pub mod entropy;
+pub mod fallback;
+pub mod fit;
+pub mod golden;
+pub mod locale;
+pub mod prior;
+pub mod session;
+pub mod stats;
+pub mod trace;
+pub mod tree;
+pub mod validate;
+pub mod wordlist;
+
+mod packed;
+mod pattern;
+pub use packed::PackedWord;
+pub use pattern::{ParsePatternError, Pattern};
 
 const DICT: &str = include_str!("../dictionary.txt");
 
+static DICTIONARY: OnceCell<Vec<(&'static str, usize)>> = OnceCell::new();
+
+/// Whether `install_dictionary` replaced the built-in dictionary.
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+static OPENER: OnceCell<&'static str> = OnceCell::new();
+
+/// Every word in the dictionary along with its frequency count, in file order. This is the
+/// built-in dictionary unless `install_dictionary` replaced it.
+pub fn dictionary() -> &'static [(&'static str, usize)] {
+    DICTIONARY.get_or_init(|| {
+        Vec::from_iter(DICT.lines().map(|line| {
+            let (word, count) = line
+                .split_once(' ')
+                .expect("every line is word + space + frequency");
+            let count: usize = count.parse().expect("every count is a number");
+            (word, count)
+        }))
+    })
+}
+
+/// Makes `words` the dictionary that every guesser, prior and game uses from now on, such as a
+/// word list loaded with `locale::Locale::load`. Fails if the dictionary has already been used,
+/// since guessers keep what they worked out from it.
+pub fn install_dictionary(words: Vec<(String, usize)>) -> Result<(), String> {
+    if DICTIONARY.get().is_some() {
+        return Err("the dictionary is already in use".to_string());
+    }
+    if words.is_empty() {
+        return Err("the dictionary has no words".to_string());
+    }
+    // The dictionary lives as long as the program, like the built-in one.
+    let words = words
+        .into_iter()
+        .map(|(word, count)| (&*Box::leak(word.into_boxed_str()), count))
+        .collect();
+    DICTIONARY
+        .set(words)
+        .map_err(|_| "the dictionary is already in use".to_string())?;
+    INSTALLED.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// The first guess of every guesser. For the built-in dictionary it is `tares`; for an installed
+/// one it is the word expected to yield the most information when words are as likely as they
+/// are frequent.
+///
+/// Using the opener fixes the dictionary, so `install_dictionary` fails after this is called.
+pub fn opener() -> &'static str {
+    OPENER.get_or_init(|| {
+        // The opener must be a word of the dictionary every later game uses.
+        dictionary();
+        if !INSTALLED.load(Ordering::SeqCst) {
+            return "tares";
+        }
+        let weights = prior::weigh(&prior::RawFrequency);
+        let answers: Vec<_> = weights
+            .iter()
+            .map(|&(word, w)| (PackedWord::new(word), w))
+            .collect();
+        let total: f64 = weights.iter().map(|&(_, w)| w).sum();
+        entropy::guess_pool()
+            .iter()
+            .map(|&(word, packed)| (word, entropy::guess_entropy(packed, &answers, total)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .expect("an installed dictionary has words")
+            .0
+    })
+}
+
 pub struct Wordle {
+    /// The guess pool: every word that is accepted as a guess.
     dict: HashSet<&'static str>,
 }
 
+impl Default for Wordle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Wordle {
     pub fn new() -> Self {
         Self {
-            dict: HashSet::from_iter(DICT.lines().map(|line| {
-                line.split_once(' ')
-                    .expect("every line is word + space + freq")
-                    .0
-            })),
+            dict: HashSet::from_iter(dictionary().iter().map(|&(word, _)| word)),
         }
     }
 
-    pub fn play<G: Guesser>(&self, ans: &'static str, mut guesser: G) -> Option<usize>{
+    /// The number of guesses `guesser` needs to find `ans`, or `None` if it doesn't within the
+    /// round limit or runs out of candidates.
+    pub fn play<G: Guesser>(&self, ans: &'static str, guesser: G) -> Option<usize>{
+        let guesses = self.guesses(ans, guesser).ok()?;
+        guesses.last().is_some_and(|g| g == ans).then_some(guesses.len())
+    }
+
+    /// Plays `ans` like `play`, but returns every guess made. The game was won if the last one
+    /// is `ans`.
+    pub fn guesses<G: Guesser>(
+        &self,
+        ans: &'static str,
+        mut guesser: G,
+    ) -> Result<Vec<String>, NoCandidates> {
         let mut hist = Vec::new();
-        for i in 1..=32 {
-            let guess = guesser.guess(&hist);
-            if guess == ans {
-                return Some(i);
-            }
-            assert!(self.dict.contains(&*guess), "guess '{}' is not in the dict", guess);
-            let correctness = Correctness::compute(ans, &guess);
+        for _ in 1..=32 {
+            let guess = guesser.guess(&hist)?;
+            let won = guess == ans;
+            assert!(won || self.dict.contains(&*guess), "guess '{}' is not in the dict", guess);
+            let correctness = Pattern::compute(ans, &guess);
             hist.push(Guess { word: Cow::Owned(guess), mask: correctness });
+            if won {
+                break;
+            }
         }
-        None
+        Ok(hist.into_iter().map(|g| g.word.into_owned()).collect())
     }
 }
 
@@ -42,40 +150,41 @@ pub enum Correctness {
 }
 
 impl Correctness {
-    fn compute(ans: &str, guess: &str) -> [Self; 5] {
+    /// The marks of each letter of `guess` when the answer is `ans`. Both must be five letters,
+    /// in any script.
+    pub fn compute(ans: &str, guess: &str) -> [Self; 5] {
+        assert_eq!(ans.chars().count(), 5);
+        assert_eq!(guess.chars().count(), 5);
+        Pattern::compute(ans, guess).to_array()
+    }
+
+    /// A slow but plainly correct `compute`, for checking faster ones against. Greens are marked
+    /// first; then each other guessed letter is yellow while the answer has copies of it left
+    /// that weren't matched already.
+    pub fn compute_reference(ans: &str, guess: &str) -> [Self; 5] {
+        let ans: Vec<char> = ans.chars().collect();
+        let guess: Vec<char> = guess.chars().collect();
         assert_eq!(ans.len(), 5);
         assert_eq!(guess.len(), 5);
-        let mut c = [Correctness::Incorrect; 5];
-        let answer_bytes = ans.as_bytes();
-        let guess_bytes = guess.as_bytes();
 
-        for (i, &a) in answer_bytes.iter().enumerate() {
-            if a == guess_bytes[i] {
-                // mark as correct
+        let mut c = [Correctness::Incorrect; 5];
+        let mut unmatched = HashMap::new();
+        for i in 0..5 {
+            if ans[i] == guess[i] {
                 c[i] = Correctness::Correct;
-            } else if let Some(j) = guess_bytes.iter().enumerate().position(|(j, &g)| {
-                // the position in guess can only be marked as Misplaced,
-                // if it isn't Correct and wasn't already marked before.
-                a == g && answer_bytes[j] != a && c[j] == Correctness::Incorrect
-            }) {
-                // Mark things yellow
-                c[j] = Correctness::Misplaced;
+            } else {
+                *unmatched.entry(ans[i]).or_insert(0usize) += 1;
+            }
+        }
+        for i in 0..5 {
+            let left = unmatched.entry(guess[i]).or_insert(0);
+            if c[i] == Correctness::Incorrect && *left > 0 {
+                c[i] = Correctness::Misplaced;
+                *left -= 1;
             }
         }
-
         c
     }
-
-    pub fn patterns() -> impl Iterator<Item = [Self; 5]> {
-        itertools::iproduct!(
-            [Self::Correct, Self::Misplaced, Self::Incorrect],
-            [Self::Correct, Self::Misplaced, Self::Incorrect],
-            [Self::Correct, Self::Misplaced, Self::Incorrect],
-            [Self::Correct, Self::Misplaced, Self::Incorrect],
-            [Self::Correct, Self::Misplaced, Self::Incorrect]
-        )
-            .map(|(a, b, c, d, e)| [a, b, c, d, e])
-    }
 }
 
 pub fn enumerate_mask(c: &[Correctness; 5]) -> usize {
@@ -91,23 +200,107 @@ pub fn enumerate_mask(c: &[Correctness; 5]) -> usize {
 
 pub const MAX_MASK_ENUM: usize = 3 * 3 * 3 * 3 * 3;
 
+#[derive(Debug, Clone)]
 pub struct Guess<'a> {
     pub word: Cow<'a, str>,
-    pub mask: [Correctness; 5],
+    pub mask: Pattern,
 }
 
 impl Guess<'_> {
     pub fn matches(&self, word: &str) -> bool {
-        return Correctness::compute(word, &self.word) == self.mask;
+        Pattern::compute(word, &self.word) == self.mask
     }
 }
 
 pub trait Guesser {
-    fn guess(&mut self, hist: &[Guess]) -> String;
+    /// The next word to guess after `hist`, or `NoCandidates` if no word the guesser considers
+    /// fits every mask so far.
+    fn guess(&mut self, hist: &[Guess]) -> Result<String, NoCandidates>;
 }
 
-impl Guesser for fn(hist: &[Guess]) -> String {
-    fn guess(&mut self, hist: &[Guess]) -> String {
+impl<G: Guesser + ?Sized> Guesser for Box<G> {
+    fn guess(&mut self, hist: &[Guess]) -> Result<String, NoCandidates> {
+        (**self).guess(hist)
+    }
+}
+
+impl<G: Guesser + ?Sized> Guesser for &mut G {
+    fn guess(&mut self, hist: &[Guess]) -> Result<String, NoCandidates> {
+        (**self).guess(hist)
+    }
+}
+
+/// No dictionary word matches every guess and mask in a history, usually because a mask was
+/// entered wrong or the answer isn't in the dictionary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoCandidates;
+
+impl std::fmt::Display for NoCandidates {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no dictionary word matches this history")
+    }
+}
+
+impl std::error::Error for NoCandidates {}
+
+/// A guesser that can settle for the best guess it finds within a time budget.
+pub trait AnytimeGuesser: Guesser {
+    /// Like `Guesser::guess`, but scores candidates most likely first and returns the best one
+    /// so far once `budget` runs out. At least one candidate is always scored, so the budget can
+    /// be overrun by the time it takes to score one.
+    fn guess_with_budget(
+        &mut self,
+        hist: &[Guess],
+        budget: Duration,
+    ) -> Result<BudgetedGuess, NoCandidates>;
+}
+
+impl<G: AnytimeGuesser + ?Sized> AnytimeGuesser for Box<G> {
+    fn guess_with_budget(
+        &mut self,
+        hist: &[Guess],
+        budget: Duration,
+    ) -> Result<BudgetedGuess, NoCandidates> {
+        (**self).guess_with_budget(hist, budget)
+    }
+}
+
+/// The result of `AnytimeGuesser::guess_with_budget`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetedGuess {
+    pub guess: String,
+    /// How many candidates were scored.
+    pub scored: usize,
+    /// How many candidates there were to score.
+    pub candidates: usize,
+    /// How many words of the guess pool the probe scan got through, or `None` if it didn't run.
+    /// It only runs once every candidate has been dealt with.
+    pub probed: Option<usize>,
+    /// Whether the search finished, so `guess` is what `Guesser::guess` would have returned.
+    /// Candidates skipped because they provably couldn't win don't count against this.
+    pub complete: bool,
+}
+
+impl BudgetedGuess {
+    /// The fraction of the search that was done: every candidate, then the guess pool if the
+    /// guesser probes, each word counting once. Candidates ruled out without scoring them count
+    /// as done, so this is 1 exactly when `complete` is true.
+    pub fn coverage(&self) -> f64 {
+        if self.complete {
+            return 1.0;
+        }
+        match self.probed {
+            Some(probed) => {
+                let pool = entropy::guess_pool().len();
+                (self.candidates + probed) as f64 / (self.candidates + pool) as f64
+            }
+            None => self.scored as f64 / self.candidates as f64,
+        }
+    }
+}
+
+impl Guesser for fn(hist: &[Guess]) -> Result<String, NoCandidates> {
+    fn guess(&mut self, hist: &[Guess]) -> Result<String, NoCandidates> {
         (*self)(hist)
     }
 }
@@ -117,7 +310,7 @@ macro_rules! guesser {
     (|$hist:ident| $impl:block) => {{
         struct G;
         impl $crate::Guesser for G {
-            fn guess(&mut self, $hist: &[Guess]) -> String {
+            fn guess(&mut self, $hist: &[Guess]) -> Result<String, $crate::NoCandidates> {
                 $impl
             }
         }
@@ -146,7 +339,7 @@ mod tests {
             ($prev:literal + [$($mask:tt)+] allows $next:literal) => {
                 assert!(Guess {
                 word: Cow::Borrowed($prev),
-                mask: mask![$($mask )+]
+                mask: mask![$($mask )+].into()
                 }
                 .matches($next));
                 assert_eq!($crate::Correctness::compute($next, $prev), mask![$($mask )+]);
@@ -154,7 +347,7 @@ mod tests {
             ($prev:literal + [$($mask:tt)+] disallows $next:literal) => {
                 assert!(!Guess {
                 word: Cow::Borrowed($prev),
-                mask: mask![$($mask )+]
+                mask: mask![$($mask )+].into()
                 }
                 .matches($next));
                 assert_ne!($crate::Correctness::compute($next, $prev), mask![$($mask )+]);
@@ -179,11 +372,18 @@ mod tests {
     mod game {
         use crate::{Guess, Wordle};
 
+        #[test]
+        fn opener_fixes_the_dictionary() {
+            assert_eq!(crate::opener(), "tares");
+            let words = vec![("abcde".to_string(), 1)];
+            assert!(crate::install_dictionary(words).is_err());
+        }
+
         #[test]
         fn genius() {
             let w = Wordle::new();
             let guesser = guesser!(|_hist| {
-                "right".to_string()
+                Ok("right".to_string())
             });
             assert_eq!(w.play("right", guesser), Some(1));
         }
@@ -193,9 +393,9 @@ mod tests {
             let w = Wordle::new();
             let guesser = guesser!(|hist| {
                 if hist.len() == 1 {
-                    return "right".to_string();
+                    return Ok("right".to_string());
                 }
-                return "wrong".to_string();
+                return Ok("wrong".to_string());
             });
             assert_eq!(w.play("right", guesser), Some(2));
         }
@@ -205,9 +405,9 @@ mod tests {
             let w = Wordle::new();
             let guesser = guesser!(|hist| {
                 if hist.len() == 2 {
-                    return "right".to_string();
+                    return Ok("right".to_string());
                 }
-                return "wrong".to_string();
+                return Ok("wrong".to_string());
             });
             assert_eq!(w.play("right", guesser), Some(3));
         }
@@ -217,9 +417,9 @@ mod tests {
             let w = Wordle::new();
             let guesser = guesser!(|hist| {
                 if hist.len() == 3 {
-                    return "right".to_string();
+                    return Ok("right".to_string());
                 }
-                return "wrong".to_string();
+                return Ok("wrong".to_string());
             });
             assert_eq!(w.play("right", guesser), Some(4));
         }
@@ -229,9 +429,9 @@ mod tests {
             let w = Wordle::new();
             let guesser = guesser!(|hist| {
                 if hist.len() == 4 {
-                    return "right".to_string();
+                    return Ok("right".to_string());
                 }
-                return "wrong".to_string();
+                return Ok("wrong".to_string());
             });
             assert_eq!(w.play("right", guesser), Some(5));
         }
@@ -241,9 +441,9 @@ mod tests {
             let w = Wordle::new();
             let guesser = guesser!(|hist| {
                 if hist.len() == 5 {
-                    return "right".to_string();
+                    return Ok("right".to_string());
                 }
-                return "wrong".to_string();
+                return Ok("wrong".to_string());
             });
             assert_eq!(w.play("right", guesser), Some(6));
         }
@@ -251,14 +451,37 @@ mod tests {
         #[test]
         fn wrong() {
             let w = Wordle::new();
-            let guesser = guesser!(|_hist| { "wrong".to_string() });
+            let guesser = guesser!(|_hist| { Ok("wrong".to_string()) });
             assert_eq!(w.play("right", guesser), None);
         }
+
+        #[test]
+        fn out_of_candidates() {
+            let w = Wordle::new();
+            let guesser = guesser!(|hist| {
+                if hist.is_empty() {
+                    return Ok("wrong".to_string());
+                }
+                Err(crate::NoCandidates)
+            });
+            assert_eq!(w.guesses("right", guesser), Err(crate::NoCandidates));
+            assert_eq!(w.play("right", guesser!(|_hist| { Err(crate::NoCandidates) })), None);
+        }
     }
 
     mod compute {
         use crate::Correctness;
 
+        #[test]
+        fn letters_not_bytes() {
+            assert_eq!(Correctness::compute("señor", "niños"), mask![I I C C M]);
+            assert_eq!(Correctness::compute("жизнь", "жизнь"), mask![C C C C C]);
+            assert_eq!(
+                Correctness::compute("grüße", "güter"),
+                Correctness::compute_reference("grüße", "güter")
+            );
+        }
+
         #[test]
         fn all_correct() {
             assert_eq!(
@@ -315,4 +538,195 @@ mod tests {
             );
         }
     }
+
+    mod pruning {
+        use crate::algorithms::{Cutoff, Enumerate};
+        use crate::Wordle;
+
+        #[test]
+        fn safe_pruning_matches_exhaustive_search() {
+            // Cutoff scores guesses exactly like Enumerate, so with the default pruning
+            // bound it must pick the same words even though it scores far fewer of them.
+            let w = Wordle::new();
+            for answer in include_str!("../answers.txt").split_whitespace().take(10) {
+                assert_eq!(
+                    w.play(answer, Cutoff::new()),
+                    w.play(answer, Enumerate::new()),
+                    "{}",
+                    answer
+                );
+            }
+        }
+    }
+
+    mod budget {
+        use crate::algorithms::{Enumerate, Sigmoid};
+        use crate::{AnytimeGuesser, BudgetedGuess, Guess, Guesser, Pattern};
+        use std::borrow::Cow;
+        use std::time::Duration;
+
+        fn after_tares(answer: &str) -> [Guess<'static>; 1] {
+            [Guess {
+                word: Cow::Borrowed("tares"),
+                mask: Pattern::compute(answer, "tares"),
+            }]
+        }
+
+        #[test]
+        fn out_of_time_scores_one_candidate() {
+            let hist = after_tares("cigar");
+            let g = Enumerate::new().guess_with_budget(&hist, Duration::ZERO).unwrap();
+            assert_eq!(g.scored, 1);
+            assert!(!g.complete);
+            assert!(g.coverage() < 1.0);
+        }
+
+        #[test]
+        fn enough_time_matches_guess() {
+            let hist = after_tares("cigar");
+            let g = Sigmoid::new()
+                .guess_with_budget(&hist, Duration::from_secs(3600))
+                .unwrap();
+            assert!(g.complete);
+            assert_eq!(g.coverage(), 1.0);
+            assert_eq!(Ok(g.guess), Sigmoid::new().guess(&hist));
+
+            let g = Enumerate::new()
+                .probing()
+                .guess_with_budget(&hist, Duration::from_secs(3600))
+                .unwrap();
+            assert_eq!(g.probed, Some(crate::entropy::guess_pool().len()));
+            assert!(g.complete);
+            assert_eq!(g.coverage(), 1.0);
+        }
+
+        #[test]
+        fn coverage_agrees_with_complete() {
+            // Every candidate was scored, but the probe scan ran out of time halfway.
+            let pool = crate::entropy::guess_pool().len();
+            let g = BudgetedGuess {
+                guess: "cigar".to_string(),
+                scored: 10,
+                candidates: 10,
+                probed: Some(pool / 2),
+                complete: false,
+            };
+            assert!(0.5 < g.coverage() && g.coverage() < 1.0, "{}", g.coverage());
+
+            // Pruning stopped early, but the search is finished.
+            let g = BudgetedGuess {
+                scored: 3,
+                probed: None,
+                complete: true,
+                ..g
+            };
+            assert_eq!(g.coverage(), 1.0);
+        }
+    }
+
+    mod reference {
+        use crate::{enumerate_mask, Correctness, Guess, PackedWord, Pattern};
+        use itertools::Itertools;
+        use rand::{Rng, SeedableRng};
+        use rand_chacha::ChaCha8Rng;
+        use std::borrow::Cow;
+
+        /// Every five-letter word over `alphabet`.
+        fn words(alphabet: &str) -> Vec<String> {
+            (0..5)
+                .map(|_| alphabet.chars())
+                .multi_cartesian_product()
+                .map(String::from_iter)
+                .collect()
+        }
+
+        /// Checks that every way of computing the mask of `guess` against `ans` agrees with
+        /// `Correctness::compute_reference`.
+        fn check(ans: &str, guess: &str) {
+            let mask = Correctness::compute_reference(ans, guess);
+            assert_eq!(Correctness::compute(ans, guess), mask, "{} {}", ans, guess);
+            assert_eq!(
+                PackedWord::new(guess).compute(PackedWord::new(ans)),
+                enumerate_mask(&mask),
+                "{} {}",
+                ans,
+                guess
+            );
+            let g = Guess {
+                word: Cow::Borrowed(guess),
+                mask: mask.into(),
+            };
+            assert!(g.matches(ans), "{} {}", ans, guess);
+        }
+
+        #[test]
+        fn mask_enumeration_round_trips() {
+            for (i, pattern) in Pattern::all().enumerate() {
+                assert_eq!(enumerate_mask(&pattern.to_array()), i);
+                assert_eq!(Pattern::from(pattern.to_array()), pattern);
+            }
+        }
+
+        #[test]
+        fn exhaustive_three_letters() {
+            let words = words("abc");
+            for ans in &words {
+                for guess in &words {
+                    check(ans, guess);
+                }
+            }
+        }
+
+        #[test]
+        fn exhaustive_matches_two_letters() {
+            // A guess and its mask allow exactly the words that would have produced that mask.
+            let words = words("ab");
+            for ans in &words {
+                for guess in &words {
+                    let g = Guess {
+                        word: Cow::Borrowed(guess),
+                        mask: Correctness::compute_reference(ans, guess).into(),
+                    };
+                    for word in &words {
+                        assert_eq!(
+                            g.matches(word),
+                            Pattern::from(Correctness::compute_reference(word, guess)) == g.mask,
+                            "{} {} {}",
+                            ans,
+                            guess,
+                            word
+                        );
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn randomised() {
+            let mut rng = ChaCha8Rng::seed_from_u64(0);
+            let alphabet: Vec<u8> = (b'a'..=b'z').collect();
+            for _ in 0..20_000 {
+                // Small alphabets make for lots of repeated letters.
+                let letters = &alphabet[..rng.gen_range(2..=alphabet.len())];
+                let mut word = || -> String {
+                    (0..5).map(|_| letters[rng.gen_range(0..letters.len())] as char).collect()
+                };
+                let (ans, guess, other) = (word(), word(), word());
+                check(&ans, &guess);
+
+                let g = Guess {
+                    word: Cow::Borrowed(&guess),
+                    mask: Correctness::compute_reference(&ans, &guess).into(),
+                };
+                assert_eq!(
+                    g.matches(&other),
+                    Pattern::from(Correctness::compute_reference(&other, &guess)) == g.mask,
+                    "{} {} {}",
+                    ans,
+                    guess,
+                    other
+                );
+            }
+        }
+    }
 }
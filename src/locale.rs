@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+
+/// The bytes that letters outside `a` to `z` are stored as, in the order the alphabet lists them.
+const SPARE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// The letters of a language, each stored as one byte so that every five-letter word is five
+/// bytes, which is what `PackedWord` and the guessers work with.
+///
+/// The letters `a` to `z` are stored as themselves, so English words are unchanged, and the
+/// others take the bytes of `SPARE` in turn: `ñ` is `A` in Spanish, and Russian has room for all
+/// 33 of its letters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+    codes: HashMap<char, u8>,
+    letters: HashMap<u8, char>,
+}
+
+impl Alphabet {
+    /// The alphabet of the lowercase letters of `letters`.
+    pub fn new(letters: &str) -> Result<Self, String> {
+        let mut codes = HashMap::new();
+        let mut spare = SPARE.iter();
+        for letter in letters.chars().filter(|c| !c.is_whitespace()) {
+            if !letter.is_alphabetic() || letter.is_uppercase() {
+                return Err(format!("'{}' is not a lowercase letter", letter));
+            }
+            let code = if letter.is_ascii_lowercase() {
+                letter as u8
+            } else {
+                let room = || format!("an alphabet has room for {} letters past z", SPARE.len());
+                *spare.next().ok_or_else(room)?
+            };
+            if codes.insert(letter, code).is_some() {
+                return Err(format!("'{}' is in the alphabet twice", letter));
+            }
+        }
+        let letters = codes.iter().map(|(&letter, &code)| (code, letter)).collect();
+        Ok(Self { codes, letters })
+    }
+
+    /// The byte `letter` is stored as, if it is in the alphabet.
+    pub fn code(&self, letter: char) -> Option<u8> {
+        self.codes.get(&letter).copied()
+    }
+
+    /// The letter stored as `code`, if it is one.
+    pub fn letter(&self, code: u8) -> Option<char> {
+        self.letters.get(&code).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+}
+
+/// How to read the words of a language: its alphabet, and the letters that are written but
+/// folded into another letter, such as accented vowels in Spanish, where `á` counts as `a` but
+/// `ñ` is a letter of its own.
+///
+/// Locales are read from lines naming the alphabet and the letters to fold, in order, into the
+/// letters after them:
+///
+/// ```text
+/// # Spanish
+/// alphabet abcdefghijklmnñopqrstuvwxyz
+/// fold áéíóúü aeiouu
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale {
+    alphabet: Alphabet,
+    folds: HashMap<char, char>,
+}
+
+impl Locale {
+    pub fn new(alphabet: Alphabet) -> Self {
+        Self {
+            alphabet,
+            folds: HashMap::new(),
+        }
+    }
+
+    /// Also reads `from` as `to`. Panics if `to` isn't in the alphabet.
+    pub fn folding(mut self, from: char, to: char) -> Self {
+        assert!(self.alphabet.code(to).is_some(), "'{}' is not in the alphabet", to);
+        self.folds.insert(from, to);
+        self
+    }
+
+    /// Reads a locale in the format described above.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut alphabet = None;
+        let mut folds = Vec::new();
+        for (i, line) in spec.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default();
+            let error = |e: String| format!("line {}: {}", i + 1, e);
+            match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [] => {}
+                ["alphabet", letters] if alphabet.is_none() => {
+                    alphabet = Some(Alphabet::new(letters).map_err(error)?);
+                }
+                ["fold", from, to] if from.chars().count() == to.chars().count() => {
+                    folds.extend(from.chars().zip(to.chars()).map(|f| (i, f)));
+                }
+                _ => return Err(error(format!("expected 'alphabet' or 'fold', not '{}'", line))),
+            }
+        }
+        let alphabet = alphabet.ok_or("there is no alphabet line")?;
+        let mut locale = Locale::new(alphabet);
+        for (i, (from, to)) in folds {
+            if locale.alphabet.code(to).is_none() {
+                return Err(format!("line {}: '{}' is not in the alphabet", i + 1, to));
+            }
+            locale = locale.folding(from, to);
+        }
+        Ok(locale)
+    }
+
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    /// `word` as the guessers store it, a byte for each letter, once it is lowercased and its
+    /// letters folded. Fails if it isn't five letters of the alphabet.
+    pub fn encode(&self, word: &str) -> Result<String, String> {
+        let mut encoded = String::with_capacity(5);
+        for letter in word.chars().flat_map(char::to_lowercase) {
+            let letter = self.folds.get(&letter).copied().unwrap_or(letter);
+            let code = self
+                .alphabet
+                .code(letter)
+                .ok_or_else(|| format!("'{}' is not in the alphabet", letter))?;
+            encoded.push(code as char);
+        }
+        if encoded.len() != 5 {
+            return Err(format!("'{}' is not five letters", word));
+        }
+        Ok(encoded)
+    }
+
+    /// The word that `encode` stored as `encoded`, with its letters folded.
+    pub fn decode(&self, encoded: &str) -> String {
+        encoded
+            .bytes()
+            .map(|code| self.alphabet.letter(code).unwrap_or('?'))
+            .collect()
+    }
+
+    /// Reads a word list in the format of the built-in dictionary, a word and its frequency
+    /// count on each line, and encodes every word. Words that fold into the same word are
+    /// merged, adding up their counts. Fails on the first line that can't be read.
+    pub fn load(&self, list: &str) -> Result<Vec<(String, usize)>, String> {
+        let mut words: Vec<(String, usize)> = Vec::new();
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        for (i, line) in list.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let error = |e: String| format!("line {}: {}", i + 1, e);
+            let (word, count) = line
+                .trim()
+                .split_once(' ')
+                .ok_or_else(|| error("expected a word and its frequency".to_string()))?;
+            let count: usize = count
+                .trim()
+                .parse()
+                .map_err(|_| error(format!("'{}' is not a frequency", count.trim())))?;
+            let word = self.encode(word).map_err(error)?;
+            match seen.get(&word) {
+                Some(&at) => words[at].1 += count,
+                None => {
+                    seen.insert(word.clone(), words.len());
+                    words.push((word, count));
+                }
+            }
+        }
+        Ok(words)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spanish() -> Locale {
+        Locale::parse("alphabet abcdefghijklmnñopqrstuvwxyz # ñ after n\nfold áéíóúü aeiouu\n")
+            .unwrap()
+    }
+
+    #[test]
+    fn letters() {
+        let russian = Alphabet::new("абвгдеёжзийклмнопрстуфхцчшщъыьэюя").unwrap();
+        assert_eq!(russian.len(), 33);
+        assert_eq!(russian.code('а'), Some(b'A'));
+        assert_eq!(russian.code('ё'), Some(b'G'));
+        assert_eq!(russian.letter(b'0'), Some('щ'));
+        assert!(Alphabet::new("abcA").is_err());
+        assert!(Alphabet::new("abca").is_err());
+        assert!(Alphabet::new("ab1").is_err());
+
+        let locale = Locale::new(russian);
+        let word = locale.encode("Жизнь").unwrap();
+        assert_eq!(word.len(), 5);
+        assert_eq!(locale.decode(&word), "жизнь");
+    }
+
+    #[test]
+    fn encodes_words() {
+        let es = spanish();
+        assert_eq!(es.alphabet().len(), 27);
+        assert_eq!(es.encode("señor"), Ok("seAor".to_string()));
+        assert_eq!(es.encode("Árbol"), Ok("arbol".to_string()));
+        assert_eq!(es.decode("seAor"), "señor");
+        assert!(es.encode("canción").is_err());
+        assert!(es.encode("straße").is_err());
+
+        let german = Locale::new(Alphabet::new("abcdefghijklmnopqrstuvwxyzäöüß").unwrap());
+        assert_eq!(german.decode(&german.encode("Grüße").unwrap()), "grüße");
+        assert!(Locale::parse("fold á a").is_err());
+        assert!(Locale::parse("alphabet abc\nfold á x").is_err());
+    }
+
+    #[test]
+    fn loads_word_lists() {
+        let words = spanish().load("limón 40\nlimon 2\n\nseñor 300\n").unwrap();
+        assert_eq!(
+            words,
+            [("limon".to_string(), 42), ("seAor".to_string(), 300)]
+        );
+        assert_eq!(
+            spanish().load("señor 300\nniño 10\n"),
+            Err("line 2: 'niño' is not five letters".to_string())
+        );
+        assert!(spanish().load("señor many\n").is_err());
+        assert!(spanish().load("señor\n").is_err());
+    }
+}
@@ -1,20 +1,362 @@
-use clap::{ArgEnum, Parser};
-use logus::Guesser;
+use clap::{ArgEnum, CommandFactory, ErrorKind, Parser, Subcommand};
+use logus::algorithms::{SigmoidParams, Table};
+use logus::daily::{Calendar, Date, DateRange};
+use logus::prior::{self, AnswerPrior};
+use logus::stats::Report;
+use logus::trace::Trace;
+use logus::tree::Tree;
+use logus::{entropy, golden, wordlist, Correctness, Guesser, Pattern};
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::collections::{HashMap, HashSet};
+use std::io::IsTerminal;
+use once_cell::sync::Lazy;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+mod human;
+mod protocol;
+mod serve;
 
 const GAMES: &str = include_str!("../answers.txt");
+const DICTIONARY: &str = include_str!("../dictionary.txt");
+
+/// How many answers `golden` plays by default for implementations that take seconds a game.
+const SLOW_GOLDEN_GAMES: usize = 100;
 
 /// information theory-based wordle-solving algorithms
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
+#[clap(subcommand_negates_reqs = true)]
 struct Args {
-    #[clap(short, long, arg_enum)]
-    implementation: Implementation,
+    #[clap(short, long, arg_enum, required = true)]
+    implementation: Option<Implementation>,
+
+    #[clap(flatten)]
+    selection: Selection,
+
+    #[clap(flatten)]
+    tuning: Tuning,
+
+    /// instead of benchmarking, play games fed in over this protocol on standard input
+    #[clap(long, arg_enum)]
+    protocol: Option<Protocol>,
 
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+/// options that choose which answers to play
+#[derive(clap::Args, Debug)]
+struct Selection {
+    /// play at most this many games
     #[clap(short, long)]
     max: Option<usize>,
+
+    /// skip this many answers of the list first
+    #[clap(long, default_value = "0")]
+    skip: usize,
+
+    /// play a random sample of this many answers
+    #[clap(long)]
+    sample: Option<usize>,
+
+    /// seed the random sample with this
+    #[clap(long, default_value = "0")]
+    seed: u64,
+
+    /// read the answers, separated by whitespace, from this file instead of the built-in list
+    #[clap(long, value_name = "FILE")]
+    answers_from: Option<PathBuf>,
+
+    /// sample the answers from the whole dictionary, weighted by frequency, instead of the
+    /// built-in list
+    #[clap(long, requires = "sample", conflicts_with_all = &["answers-from", "skip"])]
+    from_dictionary: bool,
+
+    /// play the daily puzzles of these days: a month as YYYY-MM, a day, or FIRST..LAST
+    #[clap(long, conflicts_with_all = &["skip", "sample"])]
+    range: Option<DateRange>,
+
+    /// the day of the answer list's first daily puzzle
+    #[clap(long, value_name = "DATE", default_value = "2021-06-19")]
+    epoch: Date,
+}
+
+/// options that pick the answer of a day's puzzle
+#[derive(clap::Args, Debug)]
+struct Day {
+    /// the puzzle of this day, as YYYY-MM-DD
+    #[clap(long)]
+    date: Option<Date>,
+
+    /// the day of the answer list's first puzzle
+    #[clap(long, value_name = "DATE", default_value = "2021-06-19")]
+    epoch: Date,
+
+    /// read the daily answers, in order and separated by whitespace, from this file instead of
+    /// the built-in list
+    #[clap(long, value_name = "FILE")]
+    answers_from: Option<PathBuf>,
+}
+
+/// options that change how the chosen implementation plays
+#[derive(clap::Args, Debug, Clone, Default)]
+struct Tuning {
+    /// weigh the dictionary with this prior instead of the implementation's own
+    #[clap(short, long, arg_enum)]
+    prior: Option<Prior>,
+
+    /// also guess words that can't be the answer when they split the candidates better (enum,
+    /// cutoff and sigmoid only)
+    #[clap(long)]
+    probe: bool,
+
+    /// stop scoring candidates once none could beat the best by this factor; 1 is exact
+    /// (cutoff and sigmoid only)
+    #[clap(long)]
+    prune: Option<f64>,
+
+    /// the most search iterations per guess (mcts only)
+    #[clap(long)]
+    iterations: Option<usize>,
+
+    /// the most milliseconds to search for each guess (mcts only)
+    #[clap(long)]
+    time: Option<u64>,
+
+    /// when no word fits the history, ignore the guesses that contradict it instead of giving up
+    #[clap(long)]
+    relax: bool,
+
+    /// guess from this strategy table, made by build-table, until the game leaves it
+    #[clap(long, value_name = "FILE")]
+    table: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// play a single answer and explain every turn
+    Solve {
+        #[clap(required_unless_present = "date", conflicts_with = "date")]
+        answer: Option<String>,
+
+        #[clap(flatten)]
+        day: Day,
+
+        #[clap(short, long, arg_enum)]
+        implementation: Implementation,
+
+        #[clap(flatten)]
+        tuning: Tuning,
+
+        /// how many of the best candidates to list at each turn
+        #[clap(long, default_value = "3")]
+        top: usize,
+    },
+    /// rewrite the golden corpora that the regression tests compare games against
+    Golden {
+        /// the implementations to play; every deterministic one if none are given
+        #[clap(short, long, arg_enum)]
+        implementation: Vec<Implementation>,
+
+        /// play only the first this many answers [default: all of them, or 100 for vecrem, once,
+        /// precalc and weight]
+        #[clap(short, long)]
+        max: Option<usize>,
+    },
+    /// review a game played against ANSWER, rating each guess against the best one
+    Analyse {
+        answer: String,
+
+        /// the words guessed, in order
+        #[clap(required = true)]
+        guesses: Vec<String>,
+
+        /// weigh the dictionary with this prior
+        #[clap(short, long, arg_enum, default_value = "sigmoid")]
+        prior: Prior,
+    },
+    /// play every answer and write the guesses as a tree of what is played after each pattern
+    ExportTree {
+        #[clap(short, long, arg_enum)]
+        implementation: Implementation,
+
+        #[clap(flatten)]
+        selection: Selection,
+
+        #[clap(flatten)]
+        tuning: Tuning,
+
+        /// write the tree as JSON to this file [default: standard output, unless --dot is given]
+        #[clap(long, value_name = "FILE")]
+        json: Option<PathBuf>,
+
+        /// write the tree as a Graphviz digraph to this file
+        #[clap(long, value_name = "FILE")]
+        dot: Option<PathBuf>,
+    },
+    /// play every answer and write a strategy table of the guesses for --table
+    BuildTable {
+        #[clap(short, long, arg_enum)]
+        implementation: Implementation,
+
+        #[clap(flatten)]
+        selection: Selection,
+
+        #[clap(flatten)]
+        tuning: Tuning,
+
+        /// write the table to this file
+        #[clap(short, long, value_name = "FILE")]
+        output: PathBuf,
+    },
+    /// play a game yourself against an answer from the list
+    PlayHuman {
+        /// play today's puzzle instead of a random answer
+        #[clap(long, conflicts_with_all = &["seed", "date"])]
+        daily: bool,
+
+        /// pick the random answer with this seed
+        #[clap(long, conflicts_with = "date")]
+        seed: Option<u64>,
+
+        #[clap(flatten)]
+        day: Day,
+
+        /// every green letter must stay put and every yellow one be played again
+        #[clap(long)]
+        hard: bool,
+
+        /// after each guess, show how this implementation rates it
+        #[clap(long, arg_enum, value_name = "IMPLEMENTATION")]
+        rate: Option<Implementation>,
+    },
+    /// answer guess, candidate and feedback requests over HTTP with JSON bodies
+    Serve {
+        /// the address to listen on
+        #[clap(long, default_value = "127.0.0.1:8080")]
+        addr: String,
+    },
+    /// check, combine, compare and summarise word lists in the dictionary's format
+    Dict {
+        #[clap(subcommand)]
+        command: DictCommand,
+    },
+    /// search for the sigmoid parameters that best fit the answer list
+    FitSigmoid {
+        #[clap(short, long, arg_enum, default_value = "separation")]
+        objective: Objective,
+
+        /// games to play per evaluation of the benchmark objective
+        #[clap(short, long, default_value = "100")]
+        max: usize,
+
+        /// grid points per parameter in each round of the search
+        #[clap(long, default_value = "7")]
+        steps: usize,
+
+        /// rounds of the search
+        #[clap(long, default_value = "8")]
+        rounds: usize,
+
+        /// words of the fitted curve to print either side of the cut-off
+        #[clap(long, default_value = "10")]
+        rows: usize,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum DictCommand {
+    /// report bad lines, duplicates, words that aren't five lowercase letters, and answers
+    /// missing from a word list
+    Validate {
+        /// the word list [default: the built-in dictionary]
+        list: Option<PathBuf>,
+
+        /// the answers, separated by whitespace, that must be on the list [default: the built-in
+        /// answer list]
+        #[clap(long, value_name = "FILE")]
+        answers: Option<PathBuf>,
+    },
+    /// combine word lists, keeping every word where it first appears
+    Merge {
+        #[clap(required = true)]
+        lists: Vec<PathBuf>,
+
+        /// what to do with the frequencies of a word on several lists
+        #[clap(long, arg_enum, default_value = "sum")]
+        frequencies: Frequencies,
+
+        /// write the merged list to this file [default: standard output]
+        #[clap(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// list the words added, removed and given a different frequency by NEW
+    Diff { old: PathBuf, new: PathBuf },
+    /// print the frequency distribution and entropy of a word list
+    Stats {
+        /// the word list [default: the built-in dictionary]
+        list: Option<PathBuf>,
+    },
+    /// replace the frequencies of a word list with the weights of a prior
+    Reweight {
+        /// the word list [default: the built-in dictionary]
+        list: Option<PathBuf>,
+
+        /// the prior whose weights become the frequencies
+        #[clap(long, arg_enum)]
+        prior: Prior,
+
+        /// scale the frequencies to add up to this [default: the list's own total]
+        #[clap(long)]
+        total: Option<usize>,
+
+        /// write the reweighted list to this file [default: standard output]
+        #[clap(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(ArgEnum, Debug, Clone, Copy)]
+enum Frequencies {
+    /// add them up
+    Sum,
+    /// keep the one from the last list
+    Replace,
 }
 
 #[derive(ArgEnum, Debug, Clone, Copy)]
+enum Prior {
+    /// every word is equally likely
+    Uniform,
+    /// words are as likely as they are frequent
+    Raw,
+    /// the logarithm of each word's frequency
+    Log,
+    /// the default `sigmoid` curve over each word's frequency
+    Sigmoid,
+    /// only words in the built-in answer list
+    Official,
+}
+
+#[derive(ArgEnum, Debug, Clone, Copy)]
+enum Protocol {
+    /// one JSON command per line in, one JSON response per line out
+    Jsonl,
+}
+
+#[derive(ArgEnum, Debug, Clone, Copy)]
+enum Objective {
+    /// separate the answer list from the rest of the dictionary
+    Separation,
+    /// minimise the average score of `sigmoid`
+    Benchmark,
+}
+
+#[derive(ArgEnum, Debug, Clone, Copy, PartialEq, Eq)]
 enum Implementation {
     Naive,
     Allocs,
@@ -26,87 +368,806 @@ enum Implementation {
     Cutoff,
     Popular,
     Sigmoid,
+    Mcts,
 }
 
 fn main() {
     let args = Args::parse();
 
-    match args.implementation {
-        Implementation::Naive => {
-            play(logus::algorithms::Naive::new, args.max);
+    match args.command {
+        Some(Command::Solve {
+            answer,
+            day,
+            implementation,
+            tuning,
+            top,
+        }) => {
+            tuning.check(implementation);
+            let answer = match (answer, day.date) {
+                (Some(answer), _) => answer,
+                (None, Some(date)) => day.answer(date).to_string(),
+                (None, None) => unreachable!("clap requires an answer or --date"),
+            };
+            solve(&answer, implementation, &tuning, top);
+            return;
+        }
+        Some(Command::Golden {
+            implementation,
+            max,
+        }) => {
+            golden(implementation, max);
+            return;
+        }
+        Some(Command::Analyse {
+            answer,
+            guesses,
+            prior,
+        }) => {
+            analyse(&answer, &guesses, prior);
+            return;
+        }
+        Some(Command::ExportTree {
+            implementation,
+            selection,
+            tuning,
+            json,
+            dot,
+        }) => {
+            tuning.check(implementation);
+            export_tree(implementation, &selection, &tuning, json, dot);
+            return;
         }
-        Implementation::Allocs => {
-            play(logus::algorithms::Allocs::new, args.max);
+        Some(Command::BuildTable {
+            implementation,
+            selection,
+            tuning,
+            output,
+        }) => {
+            tuning.check(implementation);
+            build_table(implementation, &selection, &tuning, &output);
+            return;
         }
-        Implementation::Vecrem => {
-            play(logus::algorithms::Vecrem::new, args.max);
+        Some(Command::PlayHuman {
+            daily,
+            seed,
+            day,
+            hard,
+            rate,
+        }) => {
+            let date = if daily { Some(Date::today()) } else { day.date };
+            human::play_human(date.map(|d| day.answer(d)), seed, hard, rate);
+            return;
         }
-        Implementation::Once => {
-            play(logus::algorithms::OnceInit::new, args.max);
+        Some(Command::Serve { addr }) => {
+            serve::serve(&addr);
+            return;
+        }
+        Some(Command::Dict { command }) => {
+            dict(command);
+            return;
+        }
+        Some(Command::FitSigmoid {
+            objective,
+            max,
+            steps,
+            rounds,
+            rows,
+        }) => {
+            fit_sigmoid(objective, max, steps, rounds, rows);
+            return;
+        }
+        None => {}
+    }
+
+    let implementation = args.implementation.expect("clap requires an implementation");
+    args.tuning.check(implementation);
+    if let Some(Protocol::Jsonl) = args.protocol {
+        protocol::run(implementation, &args.tuning);
+        return;
+    }
+    let prior = args.tuning.prior.unwrap_or(implementation.prior()).build();
+    let answers = args.selection.answers(&logus::Wordle::new());
+    play(|| implementation.guesser(&args.tuning), &prior, &answers);
+}
+
+impl Selection {
+    /// The answers to play, in order. Exits with a usage error if `--answers-from` can't be read
+    /// or lists a word that isn't in the dictionary, or if `--range` starts before the first
+    /// puzzle.
+    fn answers(&self, w: &logus::Wordle) -> Vec<&'static str> {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let answers: Vec<&'static str> = if self.from_dictionary {
+            let n = self.sample.expect("clap requires --sample");
+            logus::dictionary()
+                .choose_multiple_weighted(&mut rng, n, |&(_, count)| count as f64)
+                .expect("every count is a valid weight")
+                .map(|&(word, _)| word)
+                .collect()
+        } else if let Some(range) = self.range {
+            let calendar = calendar(w, self.epoch, self.answers_from.as_deref());
+            range.days().map(|d| daily_answer(&calendar, d)).collect()
+        } else {
+            let list = answer_list(w, self.answers_from.as_deref());
+            let list = &list[self.skip.min(list.len())..];
+            match self.sample {
+                Some(n) => list.choose_multiple(&mut rng, n).copied().collect(),
+                None => list.to_vec(),
+            }
+        };
+        answers
+            .into_iter()
+            .take(self.max.unwrap_or(usize::MAX))
+            .collect()
+    }
+}
+
+impl Implementation {
+    /// Builds a fresh guesser, adjusted by whichever of the `tuning` options it supports.
+    fn guesser(self, tuning: &Tuning) -> Box<dyn Guesser> {
+        use logus::algorithms::*;
+
+        if tuning.relax {
+            let tuning = Tuning {
+                relax: false,
+                ..tuning.clone()
+            };
+            return Box::new(logus::fallback::Relaxed::new(move || self.guesser(&tuning)));
         }
-        Implementation::Precalc => {
-            play(logus::algorithms::Precalc::new, args.max);
+        if let Some(path) = &tuning.table {
+            let table = load_table(path);
+            let tuning = Tuning {
+                table: None,
+                ..tuning.clone()
+            };
+            return Box::new(TableGuesser::new(table, move || self.guesser(&tuning)));
         }
-        Implementation::Weight => {
-            play(logus::algorithms::Weight::new, args.max);
+
+        let prior = tuning.prior.map(Prior::build);
+        macro_rules! build {
+            ($guesser:ty) => {
+                match &prior {
+                    Some(prior) => <$guesser>::with_prior(prior),
+                    None => <$guesser>::new(),
+                }
+            };
+            ($guesser:ty, probing) => {{
+                let g = build!($guesser);
+                if tuning.probe {
+                    g.probing()
+                } else {
+                    g
+                }
+            }};
+            ($guesser:ty, probing, pruning) => {{
+                let g = build!($guesser, probing);
+                match tuning.prune {
+                    Some(aggressiveness) => g.pruning(aggressiveness),
+                    None => g,
+                }
+            }};
         }
-        Implementation::Enum => {
-            play(logus::algorithms::Enumerate::new, args.max);
+
+        match self {
+            Implementation::Naive => Box::new(build!(Naive)),
+            Implementation::Allocs => Box::new(build!(Allocs)),
+            Implementation::Vecrem => Box::new(build!(Vecrem)),
+            Implementation::Once => Box::new(build!(OnceInit)),
+            Implementation::Precalc => Box::new(build!(Precalc)),
+            Implementation::Weight => Box::new(build!(Weight)),
+            Implementation::Enum => Box::new(build!(Enumerate, probing)),
+            Implementation::Cutoff => Box::new(build!(Cutoff, probing, pruning)),
+            Implementation::Popular => Box::new(build!(Popular)),
+            Implementation::Sigmoid => Box::new(build!(Sigmoid, probing, pruning)),
+            Implementation::Mcts => {
+                let mut g = build!(Mcts);
+                if let Some(iterations) = tuning.iterations {
+                    g = g.iterations(iterations);
+                }
+                if let Some(ms) = tuning.time {
+                    g = g.time_budget(Duration::from_millis(ms));
+                }
+                Box::new(g)
+            }
         }
-        Implementation::Cutoff => {
-            play(logus::algorithms::Cutoff::new, args.max);
+    }
+}
+
+impl Day {
+    /// The answer of the puzzle of `date`. Exits with a usage error if `--answers-from` can't be
+    /// read, lists a word that isn't in the dictionary, or `date` is before the first puzzle.
+    fn answer(&self, date: Date) -> &'static str {
+        let w = logus::Wordle::new();
+        let calendar = calendar(&w, self.epoch, self.answers_from.as_deref());
+        daily_answer(&calendar, date)
+    }
+}
+
+/// The answers read from `path`, or the built-in list without one. Exits with a usage error if
+/// the file can't be read or lists a word that isn't in the dictionary.
+fn answer_list(w: &logus::Wordle, path: Option<&Path>) -> Vec<&'static str> {
+    let fail = |error: String| -> ! {
+        Args::command()
+            .error(ErrorKind::InvalidValue, error)
+            .exit()
+    };
+
+    let Some(path) = path else {
+        return GAMES.split_whitespace().collect();
+    };
+    let file = std::fs::read_to_string(path)
+        .unwrap_or_else(|e| fail(format!("can't read '{}': {}", path.display(), e)));
+    let list: Vec<&'static str> = file
+        .split_whitespace()
+        .map(|word| {
+            w.word(word)
+                .unwrap_or_else(|| fail(format!("'{}' is not in the dictionary", word)))
+        })
+        .collect();
+    list
+}
+
+/// The daily puzzles of the answers from `path`, or the built-in list without one, from `epoch`.
+/// Exits with a usage error as `answer_list` does, or if there are no answers.
+fn calendar(w: &logus::Wordle, epoch: Date, path: Option<&Path>) -> Calendar {
+    let list = answer_list(w, path);
+    if list.is_empty() {
+        Args::command()
+            .error(ErrorKind::InvalidValue, "there are no answers to play one a day")
+            .exit();
+    }
+    Calendar::new(epoch, list)
+}
+
+/// The answer of the puzzle of `date`. Exits with a usage error if it is before the first one.
+fn daily_answer(calendar: &Calendar, date: Date) -> &'static str {
+    calendar.answer(date).unwrap_or_else(|| {
+        Args::command()
+            .error(
+                ErrorKind::InvalidValue,
+                format!(
+                    "{} is before the first puzzle, on {}",
+                    date,
+                    calendar.epoch()
+                ),
+            )
+            .exit()
+    })
+}
+
+impl Implementation {
+    /// Whether the implementation always plays the same game for the same answer. `naive` and
+    /// `allocs` score words in `HashMap` order, so they break ties differently from run to run.
+    fn deterministic(self) -> bool {
+        !matches!(self, Implementation::Naive | Implementation::Allocs)
+    }
+
+    /// Whether a game takes seconds, so that its golden corpus only covers the first
+    /// `SLOW_GOLDEN_GAMES` answers by default and only the ignored test replays it.
+    fn slow(self) -> bool {
+        use Implementation::*;
+        matches!(self, Vecrem | Once | Precalc | Weight)
+    }
+
+    fn name(self) -> &'static str {
+        self.to_possible_value()
+            .expect("no implementation is skipped")
+            .get_name()
+    }
+
+    /// Where the golden corpus of the implementation's games is kept.
+    fn golden_path(self) -> PathBuf {
+        [env!("CARGO_MANIFEST_DIR"), "tests", "golden"]
+            .iter()
+            .collect::<PathBuf>()
+            .join(format!("{}.txt", self.name()))
+    }
+
+    /// Plays every answer with default tuning.
+    fn games(self, answers: impl IntoIterator<Item = &'static str>) -> Vec<golden::Game> {
+        let w = logus::Wordle::new();
+        let tuning = Tuning::default();
+        answers
+            .into_iter()
+            .map(|answer| golden::Game {
+                answer: answer.to_string(),
+                guesses: w
+                    .guesses(answer, self.guesser(&tuning))
+                    .unwrap_or_else(|e| panic!("{:?} playing '{}': {}", self, answer, e)),
+            })
+            .collect()
+    }
+
+    /// The prior the implementation weighs the dictionary with unless told otherwise.
+    fn prior(self) -> Prior {
+        match self {
+            Implementation::Sigmoid | Implementation::Mcts => Prior::Sigmoid,
+            _ => Prior::Raw,
         }
-        Implementation::Popular => {
-            play(logus::algorithms::Popular::new, args.max);
+    }
+}
+
+impl Tuning {
+    /// Exits with a usage error if `implementation` doesn't support one of the chosen options.
+    fn check(&self, implementation: Implementation) {
+        if let Some(error) = self.conflict(implementation) {
+            Args::command()
+                .error(ErrorKind::ArgumentConflict, error)
+                .exit();
         }
-        Implementation::Sigmoid => {
-            play(logus::algorithms::Sigmoid::new, args.max);
+    }
+
+    /// Why `implementation` can't be tuned like this, if it can't.
+    fn conflict(&self, implementation: Implementation) -> Option<String> {
+        use Implementation::*;
+
+        let error = if self.probe && !matches!(implementation, Enum | Cutoff | Sigmoid) {
+            format!("{:?} can't guess probe words", implementation)
+        } else if self.prune.is_some() && !matches!(implementation, Cutoff | Sigmoid) {
+            format!("{:?} doesn't prune its candidates", implementation)
+        } else if self.prune.is_some_and(|a| a.is_nan() || a < 1.0) {
+            "--prune must be at least 1".to_string()
+        } else if (self.iterations.is_some() || self.time.is_some()) && implementation != Mcts {
+            format!("{:?} doesn't search with a budget", implementation)
+        } else if self.iterations == Some(0) {
+            "--iterations must be at least 1".to_string()
+        } else {
+            return None;
+        };
+        Some(error)
+    }
+}
+
+impl Prior {
+    fn build(self) -> Box<dyn AnswerPrior> {
+        match self {
+            Prior::Uniform => Box::new(prior::Uniform),
+            Prior::Raw => Box::new(prior::RawFrequency),
+            Prior::Log => Box::new(prior::LogFrequency),
+            Prior::Sigmoid => Box::new(SigmoidParams::default()),
+            Prior::Official => Box::new(prior::OfficialAnswers::new(GAMES.split_whitespace())),
         }
     }
 }
 
-fn play<G>(mut mk: impl FnMut() -> G, max: Option<usize>)
+fn play<G>(mut mk: impl FnMut() -> G, prior: &dyn AnswerPrior, answers: &[&'static str])
 where
     G: Guesser,
 {
     let w = logus::Wordle::new();
-    let mut score = 0;
-    let mut games = 0;
-    let mut histogram = Vec::new();
+    let mut traces = Vec::new();
 
-    for ans in GAMES.split_whitespace().take(max.unwrap_or(usize::MAX)) {
+    for &ans in answers {
         let guesser = (mk)();
-        if let Some(s) = w.play(ans, guesser) {
-            games += 1;
-            score += s;
-            if s >= histogram.len() {
-                histogram.extend(std::iter::repeat(0).take(s - histogram.len() + 1));
+        let trace = match w.trace(ans, guesser, prior, 0) {
+            Ok(trace) => trace,
+            Err(e) => {
+                eprintln!("{} while guessing '{}'", e, ans);
+                // Counted as a failure, without the turns that led up to it.
+                Trace {
+                    answer: ans,
+                    turns: Vec::new(),
+                    solved: false,
+                }
             }
-            histogram[s] += 1;
-            // eprintln!("guessed '{}' in {}", answer, s);
-        } else {
+        };
+        if !trace.solved {
             eprintln!("failed to guess '{}'", ans);
         }
+        traces.push(trace);
     }
-    let sum: usize = histogram.iter().sum();
-    for (score, count) in histogram.into_iter().enumerate().skip(1) {
-        let frac = count as f64 / sum as f64;
-        let w1 = (30.0 * frac).round() as usize;
-        let w2 = (30.0 * (1.0 - frac)).round() as usize;
+    let answer_entropy = entropy::remaining_entropy(prior::weigh(prior).into_iter().map(|(_, w)| w));
+    let report = Report::new(&traces, answer_entropy);
+    eprint!("{}", report);
+    match report.mean {
+        Some(mean) => println!("average score: {:.4}", mean),
+        None => println!("average score: none solved"),
+    }
+}
+
+fn solve(answer: &str, implementation: Implementation, tuning: &Tuning, top: usize) {
+    let w = logus::Wordle::new();
+    let Some(answer) = w.word(answer) else {
+        Args::command()
+            .error(
+                ErrorKind::InvalidValue,
+                format!("'{}' is not in the dictionary", answer),
+            )
+            .exit();
+    };
+
+    let prior = tuning.prior.unwrap_or(implementation.prior()).build();
+    let trace = match w.trace(answer, implementation.guesser(tuning), &prior, top) {
+        Ok(trace) => trace,
+        Err(e) => {
+            eprintln!("{} while guessing '{}'", e, answer);
+            std::process::exit(1);
+        }
+    };
+    let colour = std::io::stdout().is_terminal();
+    for (i, turn) in trace.turns.iter().enumerate() {
+        println!(
+            "{:>2}. {}  {:>5} -> {:<5} candidates  {:.2} bits gained, {:.2} expected",
+            i + 1,
+            tiles(&turn.guess, turn.mask, colour),
+            turn.before,
+            turn.after,
+            turn.gained,
+            turn.expected
+        );
+        for alt in &turn.alternatives {
+            println!(
+                "      {}  p = {:.4}  {:.2} bits  {:.2} guesses expected",
+                alt.word, alt.p, alt.entropy, alt.expected_guesses
+            );
+        }
+    }
+    if trace.solved {
+        println!("solved '{}' in {}", trace.answer, trace.turns.len());
+    } else {
+        println!("failed to guess '{}'", trace.answer);
+    }
+}
+
+fn analyse(answer: &str, guesses: &[String], prior: Prior) {
+    let w = logus::Wordle::new();
+    let guesses: Vec<&str> = guesses.iter().map(String::as_str).collect();
+    let analysis = w
+        .analyse(answer, &guesses, &prior.build())
+        .unwrap_or_else(|e| Args::command().error(ErrorKind::InvalidValue, e).exit());
+    let colour = std::io::stdout().is_terminal();
+    for (i, m) in analysis.moves.iter().enumerate() {
+        println!(
+            "{:>2}. {}  {:>5} -> {:<5} candidates  {:.2} bits expected, {:.2} gained",
+            i + 1,
+            tiles(m.guess, m.mask, colour),
+            m.before,
+            m.after,
+            m.expected,
+            m.gained
+        );
+        if m.best.1 > m.expected {
+            println!("      best: {} for {:.2} bits expected", m.best.0, m.best.1);
+        }
+        if m.eliminated {
+            println!("      could no longer be the answer");
+        }
+        if let Some(violation) = &m.hard_mode {
+            println!("      breaks hard mode: {}", violation);
+        }
+    }
+    if analysis.solved {
+        println!("solved '{}' in {}", analysis.answer, analysis.moves.len());
+    } else {
+        println!("didn't guess '{}'", analysis.answer);
+    }
+    println!("skill: {:.0}%  luck: {:+.2} bits", 100.0 * analysis.skill, analysis.luck);
+}
+
+fn dict(command: DictCommand) {
+    fn read(path: &Path) -> String {
+        std::fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("can't read '{}': {}", path.display(), e);
+            std::process::exit(1);
+        })
+    }
+    fn list(path: &Path) -> wordlist::WordList {
+        wordlist::parse(&read(path)).unwrap_or_else(|e| {
+            eprintln!("{}: {}", path.display(), e);
+            std::process::exit(1);
+        })
+    }
+
+    match command {
+        DictCommand::Validate { list, answers } => {
+            let text = list.as_deref().map_or(DICTIONARY.to_string(), read);
+            let answers = answers.as_deref().map_or(GAMES.to_string(), read);
+            let problems = wordlist::validate(&text, Some(&answers));
+            for problem in &problems {
+                println!("{}", problem);
+            }
+            if !problems.is_empty() {
+                eprintln!("{} problems", problems.len());
+                std::process::exit(1);
+            }
+            println!("{} words, no problems", text.lines().count());
+        }
+        DictCommand::Merge {
+            lists,
+            frequencies,
+            output,
+        } => {
+            let lists: Vec<_> = lists.iter().map(|path| list(path)).collect();
+            let combine = match frequencies {
+                Frequencies::Sum => wordlist::Combine::Sum,
+                Frequencies::Replace => wordlist::Combine::Replace,
+            };
+            let merged = wordlist::format(&wordlist::merge(&lists, combine));
+            match output {
+                Some(path) => write_file(&path, merged),
+                None => print!("{}", merged),
+            }
+        }
+        DictCommand::Diff { old, new } => {
+            print!("{}", wordlist::Diff::new(&list(&old), &list(&new)));
+        }
+        DictCommand::Stats { list: path } => {
+            let words = match path {
+                Some(path) => list(&path),
+                None => wordlist::parse(DICTIONARY).expect("the built-in dictionary is valid"),
+            };
+            if words.is_empty() {
+                eprintln!("the list has no words");
+                std::process::exit(1);
+            }
+            print!("{}", wordlist::Stats::new(&words));
+        }
+        DictCommand::Reweight {
+            list: path,
+            prior,
+            total,
+            output,
+        } => {
+            let words = match path {
+                Some(path) => list(&path),
+                None => wordlist::parse(DICTIONARY).expect("the built-in dictionary is valid"),
+            };
+            let total = total.unwrap_or_else(|| words.iter().map(|&(_, c)| c).sum());
+            let reweighted = wordlist::format(&wordlist::reweight(&words, &prior.build(), total));
+            match output {
+                Some(path) => write_file(&path, reweighted),
+                None => print!("{}", reweighted),
+            }
+        }
+    }
+}
+
+/// Plays every selected answer and merges the games into a tree. Exits with a usage error if the
+/// implementation isn't deterministic.
+fn policy(implementation: Implementation, selection: &Selection, tuning: &Tuning) -> Tree {
+    if !implementation.deterministic() {
+        Args::command()
+            .error(
+                ErrorKind::InvalidValue,
+                format!("{:?} doesn't always play the same game", implementation),
+            )
+            .exit();
+    }
+
+    let w = logus::Wordle::new();
+    let answers = selection.answers(&w);
+    w.tree(&answers, || implementation.guesser(tuning))
+        .unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        })
+}
+
+fn write_file(path: &Path, contents: String) {
+    std::fs::write(path, contents).unwrap_or_else(|e| {
+        eprintln!("can't write '{}': {}", path.display(), e);
+        std::process::exit(1);
+    });
+}
+
+fn export_tree(
+    implementation: Implementation,
+    selection: &Selection,
+    tuning: &Tuning,
+    json: Option<PathBuf>,
+    dot: Option<PathBuf>,
+) {
+    let tree = policy(implementation, selection, tuning);
+    let json_text = || serde_json::to_string_pretty(&tree).expect("trees serialise") + "\n";
+    match (&json, &dot) {
+        (Some(path), _) => write_file(path, json_text()),
+        (None, None) => print!("{}", json_text()),
+        (None, Some(_)) => {}
+    }
+    if let Some(path) = &dot {
+        write_file(path, tree.to_dot());
+    }
+
+    if let Some(root) = &tree.root {
         eprintln!(
-            "{:>2}: {}{} ({})",
-            score,
-            std::iter::repeat('#').take(w1).collect::<String>(),
-            std::iter::repeat(' ').take(w2).collect::<String>(),
-            count
+            "{} games, {} failed, mean {:.4}, {} guesses in a tree {} deep",
+            tree.games,
+            tree.failures,
+            tree.mean(),
+            root.size(),
+            root.depth()
         );
     }
-    println!("average score: {:.4}", score as f64 / games as f64);
+    for (guesses, &count) in tree.histogram.iter().enumerate().skip(1) {
+        eprintln!("{:>2}: {}", guesses, count);
+    }
+}
+
+fn build_table(
+    implementation: Implementation,
+    selection: &Selection,
+    tuning: &Tuning,
+    output: &Path,
+) {
+    let tree = policy(implementation, selection, tuning);
+    let table = Table::from_tree(&tree);
+    write_file(output, table.format());
+    eprintln!(
+        "{} guesses for {} games, mean {:.4}",
+        table.len(),
+        tree.games + tree.failures,
+        tree.mean()
+    );
+}
+
+/// Reads the strategy table at `path`, only once however many guessers use it. Exits with a
+/// usage error if it can't be read.
+fn load_table(path: &Path) -> Arc<Table> {
+    static TABLES: Lazy<Mutex<HashMap<PathBuf, Arc<Table>>>> = Lazy::new(Default::default);
+    let mut tables = TABLES.lock().expect("no thread panics while loading a table");
+    tables
+        .entry(path.to_path_buf())
+        .or_insert_with(|| {
+            let table = std::fs::read_to_string(path)
+                .map_err(|e| e.to_string())
+                .and_then(|text| Table::parse(&text));
+            table.map(Arc::new).unwrap_or_else(|e| {
+                Args::command()
+                    .error(
+                        ErrorKind::InvalidValue,
+                        format!("can't read the table '{}': {}", path.display(), e),
+                    )
+                    .exit()
+            })
+        })
+        .clone()
+}
+
+/// `guess` coloured by `mask`: letters on coloured backgrounds for a terminal, or emoji squares
+/// followed by the word otherwise.
+fn tiles(guess: &str, mask: Pattern, colour: bool) -> String {
+    if !colour {
+        return format!("{} {}", mask, guess);
+    }
+    guess
+        .chars()
+        .zip(mask.to_array())
+        .map(|(letter, c)| {
+            let background = match c {
+                Correctness::Correct => 42,
+                Correctness::Misplaced => 43,
+                Correctness::Incorrect => 47,
+            };
+            format!("\x1b[30;{}m{}\x1b[0m", background, letter)
+        })
+        .collect()
+}
+
+fn golden(implementations: Vec<Implementation>, max: Option<usize>) {
+    let implementations = if implementations.is_empty() {
+        Implementation::value_variants()
+            .iter()
+            .copied()
+            .filter(|i| i.deterministic())
+            .collect()
+    } else {
+        implementations
+    };
+
+    for implementation in implementations {
+        if !implementation.deterministic() {
+            eprintln!("skipping {:?}, which isn't deterministic", implementation);
+            continue;
+        }
+        let max = max.unwrap_or(if implementation.slow() {
+            SLOW_GOLDEN_GAMES
+        } else {
+            usize::MAX
+        });
+        let games = implementation.games(GAMES.split_whitespace().take(max));
+        let path = implementation.golden_path();
+        std::fs::create_dir_all(path.parent().expect("the corpus is in a directory"))
+            .and_then(|()| std::fs::write(&path, golden::format(&games)))
+            .unwrap_or_else(|e| panic!("can't write '{}': {}", path.display(), e));
+        eprintln!("wrote {} games to {}", games.len(), path.display());
+    }
 }
 
+fn fit_sigmoid(objective: Objective, max: usize, steps: usize, rounds: usize, rows: usize) {
+    let start = SigmoidParams::default();
+    let fit = match objective {
+        Objective::Separation => {
+            let answers: HashSet<_> = GAMES.split_whitespace().collect();
+            logus::fit::fit_sigmoid(start, steps, rounds, |p| {
+                logus::fit::separation(p, &answers)
+            })
+        }
+        Objective::Benchmark => {
+            let answers: Vec<_> = GAMES.split_whitespace().take(max).collect();
+            let mut fit = logus::fit::fit_sigmoid(start, steps, rounds, |p| {
+                -logus::fit::benchmark(p, &answers)
+            });
+            fit.score = -fit.score;
+            fit
+        }
+    };
+
+    let table = fit.params.table();
+    let cut = table
+        .iter()
+        .position(|row| row.sigmoid >= fit.params.l / 2.0)
+        .unwrap_or(table.len());
+    eprintln!(" word  \"raw\" p      sigmoid p   count");
+    for row in &table[cut.saturating_sub(rows)..(cut + rows).min(table.len())] {
+        eprintln!("{}", row);
+    }
+    println!(
+        "k = {:e}, x0 = {:e}, l = {} ({:?} {:.4} after {} evaluations)",
+        fit.params.k, fit.params.x0, fit.params.l, objective, fit.score, fit.evaluations
+    );
+}
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    /// How many games of each fast implementation's corpus the cheap regression test replays.
+    const CHEAP_GAMES: usize = 10;
+
+    /// Replays the first `max` games of `implementation`'s golden corpus, failing with the games
+    /// that changed.
+    fn check_golden(implementation: Implementation, max: usize) {
+        let path = implementation.golden_path();
+        let corpus = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("can't read '{}': {}", path.display(), e));
+        let expected = golden::parse(&corpus).unwrap();
+        let expected = &expected[..max.min(expected.len())];
+
+        let w = logus::Wordle::new();
+        let answers = expected.iter().map(|g| w.word(&g.answer).expect("answers are words"));
+        let actual = implementation.games(answers);
+        if let Some(diff) = golden::diff(expected, &actual) {
+            panic!(
+                "{:?} plays differently; run `cargo run --release -- golden -i {}` if that's \
+                 intended\n{}",
+                implementation,
+                implementation.name(),
+                diff
+            );
+        }
+    }
+
+    #[test]
+    fn golden_cheap() {
+        for &implementation in Implementation::value_variants() {
+            if implementation.deterministic() && !implementation.slow() {
+                check_golden(implementation, CHEAP_GAMES);
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "replays every golden game, which takes hours"]
+    fn golden_full() {
+        for &implementation in Implementation::value_variants() {
+            if implementation.deterministic() {
+                check_golden(implementation, usize::MAX);
+            }
+        }
+    }
+
+    #[test]
+    fn tables_are_cached_by_path() {
+        let dir = std::env::temp_dir().join(format!("logus-tables-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (short, long) = (dir.join("short.txt"), dir.join("long.txt"));
+        std::fs::write(&short, "tares\n").unwrap();
+        std::fs::write(&long, "tares\nBYYBB drain\n").unwrap();
+        assert_eq!(load_table(&short).len(), 1);
+        assert_eq!(load_table(&long).len(), 2);
+        assert!(Arc::ptr_eq(&load_table(&short), &load_table(&short)));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn first_10_games_with_cutoff() {
         let w = logus::Wordle::new();
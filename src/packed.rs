@@ -0,0 +1,189 @@
+use crate::MAX_MASK_ENUM;
+
+/// The weight of each position in an `enumerate_mask` index; the first letter is the most
+/// significant digit.
+const PLACE: [usize; 5] = [81, 27, 9, 3, 1];
+
+// Every position starts out Incorrect (2), so an all-grey mask is `2 * (81 + 27 + 9 + 3 + 1)`.
+const ALL_INCORRECT: usize = 2 * 121;
+
+/// A five-letter word packed into the low 40 bits of a `u64`, one byte per letter.
+///
+/// Comparing two packed words is a handful of integer operations instead of a walk over two
+/// `&str`s, which is what makes it suitable for the inner loop of the entropy guessers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PackedWord(u64);
+
+impl PackedWord {
+    pub fn new(word: &str) -> Self {
+        let bytes = word.as_bytes();
+        assert_eq!(bytes.len(), 5, "'{}' is not a five-letter word", word);
+        Self::from_bytes([bytes[0], bytes[1], bytes[2], bytes[3], bytes[4]])
+    }
+
+    pub fn from_bytes(letters: [u8; 5]) -> Self {
+        Self(
+            letters
+                .iter()
+                .enumerate()
+                .fold(0, |acc, (i, &l)| acc | (l as u64) << (8 * i)),
+        )
+    }
+
+    #[inline]
+    pub fn letter(self, i: usize) -> u8 {
+        (self.0 >> (8 * i)) as u8
+    }
+
+    pub fn letters(self) -> [u8; 5] {
+        [
+            self.letter(0),
+            self.letter(1),
+            self.letter(2),
+            self.letter(3),
+            self.letter(4),
+        ]
+    }
+
+    /// Computes the mask produced by guessing `self` when the answer is `answer`, returned as
+    /// its `enumerate_mask` index.
+    #[inline]
+    pub fn compute(self, answer: PackedWord) -> usize {
+        // A zero byte in the xor is a letter in the right place.
+        let diff = self.0 ^ answer.0;
+        let mut green = 0u8;
+        for i in 0..5 {
+            if (diff >> (8 * i)) as u8 == 0 {
+                green |= 1 << i;
+            }
+        }
+        if green == 0b11111 {
+            return 0;
+        }
+
+        let mut idx = ALL_INCORRECT;
+        // Answer letters that have not yet been matched by a guess letter.
+        let mut unused = !green & 0b11111;
+        // A cheap (possibly over-inclusive) set of the letters in `unused`, so that most grey
+        // letters never have to scan the answer.
+        let mut present = 0u64;
+        for j in 0..5 {
+            if unused & (1 << j) != 0 {
+                present |= 1 << (answer.letter(j) & 63);
+            }
+        }
+        for (i, place) in PLACE.into_iter().enumerate() {
+            if green & (1 << i) != 0 {
+                idx -= 2 * place;
+                continue;
+            }
+            let g = self.letter(i);
+            if present & (1 << (g & 63)) == 0 {
+                continue;
+            }
+            // The leftmost unmatched copy of this letter in the answer makes this one yellow.
+            for j in 0..5 {
+                if unused & (1 << j) != 0 && answer.letter(j) == g {
+                    unused &= !(1 << j);
+                    idx -= place;
+                    break;
+                }
+            }
+        }
+        idx
+    }
+
+    /// Computes the mask of guessing `self` against every word in `answers`, writing the
+    /// `enumerate_mask` indices into `out`.
+    pub fn compute_batch(self, answers: &[PackedWord], out: &mut [u8]) {
+        assert_eq!(answers.len(), out.len());
+        for (answer, o) in answers.iter().zip(out) {
+            *o = self.compute(*answer) as u8;
+        }
+    }
+
+    /// Counts how many of `answers` fall into each of the masks that guessing `self` could
+    /// produce.
+    pub fn pattern_counts(self, answers: &[PackedWord]) -> [usize; MAX_MASK_ENUM] {
+        let mut totals = [0usize; MAX_MASK_ENUM];
+        for answer in answers {
+            totals[self.compute(*answer)] += 1;
+        }
+        totals
+    }
+
+    /// Like `pattern_counts`, but every answer contributes its weight rather than one.
+    pub fn pattern_weights<W>(self, answers: &[(PackedWord, W)]) -> [f64; MAX_MASK_ENUM]
+    where
+        W: Copy + Into<f64>,
+    {
+        let mut totals = [0.0f64; MAX_MASK_ENUM];
+        for &(answer, weight) in answers {
+            totals[self.compute(answer)] += weight.into();
+        }
+        totals
+    }
+}
+
+impl From<&str> for PackedWord {
+    fn from(word: &str) -> Self {
+        Self::new(word)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PackedWord;
+    use crate::Correctness::{Correct as C, Incorrect as I, Misplaced as M};
+    use crate::{enumerate_mask, Correctness};
+
+    fn index(answer: &str, guess: &str) -> usize {
+        PackedWord::new(guess).compute(PackedWord::new(answer))
+    }
+
+    #[test]
+    fn round_trip() {
+        let w = PackedWord::new("cigar");
+        assert_eq!(&w.letters(), b"cigar");
+    }
+
+    #[test]
+    fn all_correct() {
+        assert_eq!(index("abcde", "abcde"), 0);
+    }
+
+    #[test]
+    fn matches_enumerate_mask() {
+        for (answer, guess, mask) in [
+            ("abcde", "fghij", [I, I, I, I, I]),
+            ("abcde", "eabcd", [M, M, M, M, M]),
+            ("aabbb", "aaccc", [C, C, I, I, I]),
+            ("aabbb", "ccaac", [I, I, M, M, I]),
+            ("aabbb", "caacc", [I, C, M, I, I]),
+            ("azzaz", "aaabb", [C, M, I, I, I]),
+        ] {
+            assert_eq!(index(answer, guess), enumerate_mask(&mask), "{} {}", answer, guess);
+        }
+    }
+
+    #[test]
+    fn batch() {
+        let guess = PackedWord::new("tares");
+        let answers: Vec<_> = ["cigar", "rebut", "sissy", "tares"]
+            .into_iter()
+            .map(PackedWord::new)
+            .collect();
+        let mut out = vec![0; answers.len()];
+        guess.compute_batch(&answers, &mut out);
+        for (answer, &idx) in answers.iter().zip(&out) {
+            assert_eq!(idx as usize, guess.compute(*answer));
+        }
+        let counts = guess.pattern_counts(&answers);
+        assert_eq!(counts.iter().sum::<usize>(), 4);
+        assert_eq!(counts[0], 1);
+        assert_eq!(
+            counts[enumerate_mask(&Correctness::compute("cigar", "tares"))],
+            1
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{enumerate_mask, Correctness, PackedWord, MAX_MASK_ENUM};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// A mask, stored as its `enumerate_mask` index so that it takes a single byte.
+///
+/// Patterns order by index, which puts the winning pattern first, and the first letter is the
+/// most significant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Pattern(u8);
+
+impl Pattern {
+    /// Every letter in the right place.
+    pub const WIN: Self = Self(0);
+
+    /// The pattern with `enumerate_mask` index `idx`.
+    pub fn from_index(idx: usize) -> Self {
+        assert!(idx < MAX_MASK_ENUM, "{} is not a mask index", idx);
+        Self(idx as u8)
+    }
+
+    /// The pattern seen when guessing `guess` if the answer is `ans`. Words that aren't five
+    /// ASCII letters, such as ones `locale::Locale` hasn't encoded, take a slower path.
+    pub fn compute(ans: &str, guess: &str) -> Self {
+        let ascii = |word: &str| word.len() == 5 && word.is_ascii();
+        let (ans, guess) = if ascii(ans) && ascii(guess) {
+            (PackedWord::new(ans), PackedWord::new(guess))
+        } else {
+            pack_letters(ans, guess)
+        };
+        Self(guess.compute(ans) as u8)
+    }
+
+    /// Every pattern, in index order.
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..MAX_MASK_ENUM as u8).map(Self)
+    }
+
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    pub fn to_array(self) -> [Correctness; 5] {
+        let mut idx = self.0;
+        let mut c = [Correctness::Correct; 5];
+        for slot in c.iter_mut().rev() {
+            *slot = match idx % 3 {
+                0 => Correctness::Correct,
+                1 => Correctness::Misplaced,
+                _ => Correctness::Incorrect,
+            };
+            idx /= 3;
+        }
+        c
+    }
+
+    pub fn is_win(self) -> bool {
+        self == Self::WIN
+    }
+}
+
+/// `ans` and `guess` packed with a byte for each letter. Only which letters are equal matters to
+/// a mask, so every distinct letter of the two words gets its own byte.
+fn pack_letters(ans: &str, guess: &str) -> (PackedWord, PackedWord) {
+    let mut seen: Vec<char> = Vec::with_capacity(10);
+    let mut pack = |word: &str| {
+        let mut bytes = [0; 5];
+        let mut chars = word.chars();
+        for b in &mut bytes {
+            let c = chars.next();
+            let c = c.unwrap_or_else(|| panic!("'{}' is not a five-letter word", word));
+            let i = seen.iter().position(|&s| s == c).unwrap_or_else(|| {
+                seen.push(c);
+                seen.len() - 1
+            });
+            *b = b'a' + i as u8;
+        }
+        assert!(chars.next().is_none(), "'{}' is not a five-letter word", word);
+        PackedWord::from_bytes(bytes)
+    };
+    (pack(ans), pack(guess))
+}
+
+impl From<[Correctness; 5]> for Pattern {
+    fn from(c: [Correctness; 5]) -> Self {
+        Self(enumerate_mask(&c) as u8)
+    }
+}
+
+impl From<Pattern> for [Correctness; 5] {
+    fn from(p: Pattern) -> Self {
+        p.to_array()
+    }
+}
+
+/// Shows the pattern as coloured squares, like the game's share text, or with `{:#}` as the
+/// letters `G`, `Y` and `B` for green, yellow and black.
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.to_array() {
+            let c = match (f.alternate(), c) {
+                (false, Correctness::Correct) => '🟩',
+                (false, Correctness::Misplaced) => '🟨',
+                (false, Correctness::Incorrect) => '⬛',
+                (true, Correctness::Correct) => 'G',
+                (true, Correctness::Misplaced) => 'Y',
+                (true, Correctness::Incorrect) => 'B',
+            };
+            write!(f, "{}", c)?;
+        }
+        Ok(())
+    }
+}
+
+/// Serialises as the letters `{:#}` writes, so that patterns can key JSON objects.
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{:#}", self))
+    }
+}
+
+/// Deserialises from either form `FromStr` reads.
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePatternError(String);
+
+impl fmt::Display for ParsePatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "'{}' is not a pattern: expected five of 🟩🟨⬛ or G, Y and B",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParsePatternError {}
+
+/// Reads either form that `Display` writes, in any case. `⬜` is also grey, as in the game's
+/// light theme.
+impl FromStr for Pattern {
+    type Err = ParsePatternError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParsePatternError(s.to_string());
+        let mut c = [Correctness::Correct; 5];
+        let mut chars = s.trim().chars();
+        for slot in &mut c {
+            *slot = match chars.next().ok_or_else(error)? {
+                '🟩' | 'G' | 'g' => Correctness::Correct,
+                '🟨' | 'Y' | 'y' => Correctness::Misplaced,
+                '⬛' | '⬜' | 'B' | 'b' => Correctness::Incorrect,
+                _ => return Err(error()),
+            };
+        }
+        if chars.next().is_some() {
+            return Err(error());
+        }
+        Ok(c.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Correctness::{Correct as C, Incorrect as I, Misplaced as M};
+
+    #[test]
+    fn index_round_trips() {
+        for (i, p) in Pattern::all().enumerate() {
+            assert_eq!(p.index(), i);
+            assert_eq!(Pattern::from(p.to_array()), p);
+            assert_eq!(Pattern::from_index(i), p);
+        }
+        assert_eq!(Pattern::all().count(), MAX_MASK_ENUM);
+    }
+
+    #[test]
+    fn text() {
+        let p = Pattern::compute("cigar", "tares");
+        assert_eq!(p.to_array(), [I, M, M, I, I]);
+        assert_eq!(p.to_string(), "⬛🟨🟨⬛⬛");
+        assert_eq!(format!("{:#}", p), "BYYBB");
+        assert_eq!("⬛🟨🟨⬛⬛".parse(), Ok(p));
+        assert_eq!("byYbB".parse(), Ok(p));
+        assert_eq!("⬜🟨🟨⬜⬜".parse(), Ok(p));
+        assert!("BYYB".parse::<Pattern>().is_err());
+        assert!("BYYBBB".parse::<Pattern>().is_err());
+        assert!("BYYBZ".parse::<Pattern>().is_err());
+        assert_eq!(serde_json::to_string(&p).unwrap(), "\"BYYBB\"");
+        assert_eq!(serde_json::from_str("\"⬛🟨🟨⬛⬛\"").ok(), Some(p));
+    }
+
+    #[test]
+    fn wins_come_first() {
+        assert!(Pattern::compute("cigar", "cigar").is_win());
+        assert!(!Pattern::from([C, C, C, C, M]).is_win());
+        assert_eq!(Pattern::all().min(), Some(Pattern::WIN));
+        assert!(Pattern::from([C, M, I, I, I]) < Pattern::from([M, C, C, C, C]));
+    }
+}
@@ -0,0 +1,140 @@
+use crate::algorithms::SigmoidParams;
+use crate::dictionary;
+use std::collections::HashSet;
+
+/// How likely a dictionary word is to be the answer, before any guesses have been made.
+///
+/// The weights don't need to sum to one: the guessers only ever compare them relative to the
+/// total weight of the words that are still possible.
+pub trait AnswerPrior {
+    /// The weight of `word`, which makes up `count` of the dictionary's `total` frequency.
+    fn weight(&self, word: &str, count: usize, total: usize) -> f64;
+}
+
+impl<P: AnswerPrior + ?Sized> AnswerPrior for &P {
+    fn weight(&self, word: &str, count: usize, total: usize) -> f64 {
+        (**self).weight(word, count, total)
+    }
+}
+
+impl<P: AnswerPrior + ?Sized> AnswerPrior for Box<P> {
+    fn weight(&self, word: &str, count: usize, total: usize) -> f64 {
+        (**self).weight(word, count, total)
+    }
+}
+
+/// Every word is equally likely.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Uniform;
+
+impl AnswerPrior for Uniform {
+    fn weight(&self, _: &str, _: usize, _: usize) -> f64 {
+        1.0
+    }
+}
+
+/// Words are as likely as they are frequent.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RawFrequency;
+
+impl AnswerPrior for RawFrequency {
+    fn weight(&self, _: &str, count: usize, _: usize) -> f64 {
+        count as f64
+    }
+}
+
+/// Like `RawFrequency`, but compressed so that the most common words don't drown out the rest.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogFrequency;
+
+impl AnswerPrior for LogFrequency {
+    fn weight(&self, _: &str, count: usize, _: usize) -> f64 {
+        (count as f64).ln_1p()
+    }
+}
+
+impl AnswerPrior for SigmoidParams {
+    fn weight(&self, _: &str, count: usize, total: usize) -> f64 {
+        self.apply(count as f64 / total as f64)
+    }
+}
+
+/// Only words on a known answer list are likely; everything else gets a small residual weight so
+/// that games whose answer is missing from the list can still be finished.
+#[derive(Debug, Clone)]
+pub struct OfficialAnswers {
+    answers: HashSet<String>,
+    residual: f64,
+}
+
+impl OfficialAnswers {
+    pub fn new<'a>(answers: impl IntoIterator<Item = &'a str>) -> Self {
+        Self {
+            answers: answers.into_iter().map(String::from).collect(),
+            residual: 1e-6,
+        }
+    }
+
+    /// Sets the weight of words that aren't on the list.
+    pub fn residual(mut self, residual: f64) -> Self {
+        self.residual = residual;
+        self
+    }
+}
+
+impl AnswerPrior for OfficialAnswers {
+    fn weight(&self, word: &str, _: usize, _: usize) -> f64 {
+        if self.answers.contains(word) {
+            1.0
+        } else {
+            self.residual
+        }
+    }
+}
+
+/// Every dictionary word with its weight under `prior`, in dictionary order.
+pub fn weigh<P: AnswerPrior + ?Sized>(prior: &P) -> Vec<(&'static str, f64)> {
+    let total: usize = dictionary().iter().map(|&(_, count)| count).sum();
+    dictionary()
+        .iter()
+        .map(|&(word, count)| (word, prior.weight(word, count, total)))
+        .collect()
+}
+
+/// Like `weigh`, but with the most likely words first. Words of equal weight are ordered by
+/// frequency.
+pub fn weigh_sorted<P: AnswerPrior + ?Sized>(prior: &P) -> Vec<(&'static str, f64)> {
+    let total: usize = dictionary().iter().map(|&(_, count)| count).sum();
+    let mut words: Vec<_> = dictionary()
+        .iter()
+        .map(|&(word, count)| (word, count, prior.weight(word, count, total)))
+        .collect();
+    words.sort_unstable_by(|a, b| b.2.total_cmp(&a.2).then(b.1.cmp(&a.1)));
+    words
+        .into_iter()
+        .map(|(word, _, weight)| (word, weight))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn official_answers() {
+        let prior = OfficialAnswers::new(["cigar", "rebut"]).residual(0.5);
+        assert_eq!(prior.weight("cigar", 1, 10), 1.0);
+        assert_eq!(prior.weight("tares", 1, 10), 0.5);
+    }
+
+    #[test]
+    fn weigh_keeps_dictionary_order() {
+        let weights = weigh(&RawFrequency);
+        assert_eq!(weights.len(), dictionary().len());
+        for (&(word, count), &(w, weight)) in dictionary().iter().zip(&weights) {
+            assert_eq!(word, w);
+            assert_eq!(count as f64, weight);
+        }
+        assert!(weigh(&LogFrequency).iter().all(|&(_, w)| w >= 0.0));
+    }
+}
@@ -0,0 +1,203 @@
+use crate::serve::{implementation, prior, session, Candidate, GameSession};
+use crate::{Implementation, Tuning};
+use logus::trace::suggestions;
+use logus::Pattern;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+
+/// A command of the `jsonl` protocol, named by its `command` field. Any `id` field is echoed
+/// back in the response.
+#[derive(Deserialize, Debug)]
+#[serde(tag = "command", rename_all = "snake_case")]
+enum Command {
+    /// Starts a new game, playing `implementation` if given.
+    NewGame { implementation: Option<String> },
+    /// Records that guessing `word` showed `mask`.
+    Feedback { word: String, mask: Pattern },
+    /// Asks for the next guess, and the `top` best candidates by expected guesses.
+    Guess {
+        #[serde(default)]
+        top: usize,
+    },
+    /// Lists the words that fit the game so far, most likely first.
+    Candidates { limit: Option<usize> },
+    /// Starts a new game with the implementation and tuning given on the command line.
+    Reset,
+}
+
+/// The one game the protocol plays at a time.
+struct Game {
+    start: Implementation,
+    tuning: Tuning,
+    implementation: Implementation,
+    /// The tuning `session` was built with, which is `tuning` only while playing `start`.
+    current: Tuning,
+    session: GameSession,
+}
+
+impl Game {
+    fn new(implementation: Implementation, tuning: &Tuning) -> Self {
+        Self {
+            start: implementation,
+            tuning: tuning.clone(),
+            implementation,
+            current: tuning.clone(),
+            session: session(implementation, tuning),
+        }
+    }
+
+    /// The response to a line of input.
+    fn respond(&mut self, line: &str) -> Value {
+        let mut request: Value = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(e) => return json!({ "ok": false, "error": e.to_string() }),
+        };
+        let id = request.as_object_mut().and_then(|r| r.remove("id"));
+        let mut response = match serde_json::from_value(request) {
+            Ok(command) => self.run(command),
+            Err(e) => Err(e.to_string()),
+        }
+        .unwrap_or_else(|error| json!({ "ok": false, "error": error }));
+        if let Some(id) = id {
+            response["id"] = id;
+        }
+        response
+    }
+
+    fn run(&mut self, command: Command) -> Result<Value, String> {
+        match command {
+            Command::NewGame { implementation: name } => {
+                let implementation = match name {
+                    Some(name) => implementation(Some(&name)).map_err(|e| e.message)?,
+                    None => self.implementation,
+                };
+                // Tuning options given for the first implementation may not suit another.
+                let tuning = if implementation == self.start {
+                    self.tuning.clone()
+                } else {
+                    Tuning::default()
+                };
+                self.implementation = implementation;
+                self.session = session(implementation, &tuning);
+                self.current = tuning;
+                Ok(json!({ "ok": true, "implementation": implementation.name() }))
+            }
+            Command::Reset => {
+                *self = Game::new(self.start, &self.tuning);
+                Ok(json!({ "ok": true, "implementation": self.start.name() }))
+            }
+            Command::Feedback { word, mask } => {
+                self.session
+                    .feedback(&word, mask)
+                    .map_err(|e| e.to_string())?;
+                let candidates = self.session.candidates(&self.prior()).len();
+                Ok(json!({ "ok": true, "candidates": candidates, "solved": mask.is_win() }))
+            }
+            Command::Guess { top } => {
+                let guess = self.session.guess().map_err(|e| e.to_string())?;
+                let suggestions = suggestions(&self.prior(), self.session.history(), top);
+                Ok(json!({ "ok": true, "guess": guess, "suggestions": suggestions }))
+            }
+            Command::Candidates { limit } => {
+                let candidates = self.session.candidates(&self.prior());
+                let count = candidates.len();
+                let candidates: Vec<_> = candidates
+                    .into_iter()
+                    .take(limit.unwrap_or(usize::MAX))
+                    .map(|(word, p)| Candidate { word, p })
+                    .collect();
+                Ok(json!({ "ok": true, "count": count, "candidates": candidates }))
+            }
+        }
+    }
+
+    fn prior(&self) -> Box<dyn logus::prior::AnswerPrior> {
+        prior(self.implementation, &self.current)
+    }
+}
+
+/// Answers commands from standard input, one line each, until it ends.
+pub fn run(implementation: Implementation, tuning: &Tuning) {
+    let mut game = Game::new(implementation, tuning);
+    let mut out = io::stdout().lock();
+    for line in io::stdin().lock().lines() {
+        let line = line.unwrap_or_else(|e| {
+            eprintln!("can't read standard input: {}", e);
+            std::process::exit(1);
+        });
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = game.respond(&line);
+        if writeln!(out, "{}", response).and_then(|_| out.flush()).is_err() {
+            // Whoever was reading has gone.
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plays_a_game() {
+        let mut game = Game::new(Implementation::Cutoff, &Tuning::default());
+        let guess = game.respond(r#"{"command": "guess", "id": 1}"#);
+        assert_eq!(guess, json!({ "ok": true, "guess": "tares", "suggestions": [], "id": 1 }));
+
+        let line = r#"{"command": "feedback", "word": "tares", "mask": "BYYBB"}"#;
+        let feedback = game.respond(line);
+        assert_eq!(feedback["ok"], true);
+        assert_eq!(feedback["solved"], false);
+        let candidates = game.respond(r#"{"command": "candidates", "limit": 2}"#);
+        assert_eq!(candidates["count"], feedback["candidates"]);
+        assert_eq!(candidates["candidates"].as_array().unwrap().len(), 2);
+
+        let next = game.respond(r#"{"command": "guess"}"#);
+        assert_ne!(next["guess"], "tares");
+
+        let line = r#"{"command": "new_game", "implementation": "popular"}"#;
+        assert_eq!(game.respond(line)["implementation"], "popular");
+        let candidates = game.respond(r#"{"command": "candidates"}"#);
+        assert_eq!(candidates["count"], logus::dictionary().len());
+        assert_eq!(game.respond(r#"{"command": "reset"}"#)["implementation"], "cutoff");
+    }
+
+    #[test]
+    fn ranks_with_the_sessions_prior() {
+        let tuning = Tuning {
+            prior: Some(crate::Prior::Uniform),
+            ..Tuning::default()
+        };
+        let mut game = Game::new(Implementation::Cutoff, &tuning);
+        let top = |game: &mut Game| game.respond(r#"{"command": "candidates", "limit": 1}"#);
+        let uniform = top(&mut game)["candidates"][0]["p"].as_f64().unwrap();
+        assert_eq!(uniform, 1.0 / logus::dictionary().len() as f64);
+
+        // Popular is played with its own prior, so the candidates are ranked by it too.
+        game.respond(r#"{"command": "new_game", "implementation": "popular"}"#);
+        let raw = top(&mut game)["candidates"][0]["p"].as_f64().unwrap();
+        assert!(raw > uniform, "{}", raw);
+
+        game.respond(r#"{"command": "new_game", "implementation": "cutoff"}"#);
+        assert_eq!(top(&mut game)["candidates"][0]["p"].as_f64(), Some(uniform));
+    }
+
+    #[test]
+    fn reports_errors() {
+        let mut game = Game::new(Implementation::Cutoff, &Tuning::default());
+        for line in [
+            "not json",
+            r#"{"command": "dance"}"#,
+            r#"{"command": "feedback", "word": "tares"}"#,
+            r#"{"command": "feedback", "word": "xxxxx", "mask": "BBBBB"}"#,
+            r#"{"command": "new_game", "implementation": "magic"}"#,
+        ] {
+            let response = game.respond(line);
+            assert_eq!(response["ok"], false, "{}", line);
+            assert!(response["error"].is_string(), "{}", line);
+        }
+    }
+}
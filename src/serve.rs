@@ -0,0 +1,357 @@
+use crate::{Implementation, Tuning};
+use clap::ArgEnum;
+use logus::prior::AnswerPrior;
+use logus::session::Session;
+use logus::trace::{suggestions, Alternative};
+use logus::{Guesser, Pattern};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::io::Read;
+use tiny_http::{Header, Method, Response, Server};
+
+/// The largest request body read, in bytes.
+const MAX_BODY: u64 = 1 << 20;
+
+pub type GameSession = Session<Box<dyn Guesser>, Box<dyn FnMut() -> Box<dyn Guesser>>>;
+
+/// The solver behind `serve`: stateless requests, and games kept between requests by session id.
+#[derive(Default)]
+pub struct Solver {
+    sessions: HashMap<String, (Implementation, GameSession)>,
+    tuning: Tuning,
+}
+
+/// A request that failed, with the HTTP status that says why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiError {
+    pub status: u16,
+    pub message: String,
+}
+
+impl ApiError {
+    fn new(status: u16, message: impl ToString) -> Self {
+        Self {
+            status,
+            message: message.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Entry {
+    pub word: String,
+    pub mask: Pattern,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct GuessRequest {
+    /// Defaults to sigmoid.
+    pub implementation: Option<String>,
+    /// Keeps the guesser between requests under this id, so that each only pays for the turns
+    /// added since the last.
+    pub session: Option<String>,
+    #[serde(default)]
+    pub history: Vec<Entry>,
+    /// How many ranked suggestions to list.
+    #[serde(default = "default_top")]
+    pub top: usize,
+}
+
+fn default_top() -> usize {
+    3
+}
+
+#[derive(Serialize, Debug)]
+pub struct GuessResponse {
+    pub guess: String,
+    pub candidates: usize,
+    pub suggestions: Vec<Alternative>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct CandidatesRequest {
+    /// Whose prior ranks the candidates; defaults to sigmoid.
+    pub implementation: Option<String>,
+    #[serde(default)]
+    pub history: Vec<Entry>,
+    /// List at most this many candidates.
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Candidate {
+    pub word: &'static str,
+    pub p: f64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CandidatesResponse {
+    pub count: usize,
+    pub candidates: Vec<Candidate>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct FeedbackRequest {
+    pub guess: String,
+    pub answer: String,
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The next guess after `req.history`, and the best candidates by expected guesses.
+    pub fn guess(&mut self, req: GuessRequest) -> Result<GuessResponse, ApiError> {
+        let implementation = implementation(req.implementation.as_deref())?;
+        let entries = entries(&req.history);
+        let tuning = &self.tuning;
+        let mut scratch;
+        let session = match req.session {
+            Some(id) => {
+                let game = self
+                    .sessions
+                    .entry(id)
+                    .or_insert_with(|| (implementation, session(implementation, tuning)));
+                if game.0 != implementation {
+                    *game = (implementation, session(implementation, tuning));
+                }
+                &mut game.1
+            }
+            None => {
+                scratch = session(implementation, tuning);
+                &mut scratch
+            }
+        };
+        session
+            .set_history(&entries)
+            .map_err(|e| ApiError::new(422, e))?;
+        let guess = session.guess().map_err(|e| ApiError::new(422, e))?;
+
+        let prior = prior(implementation, tuning);
+        Ok(GuessResponse {
+            guess,
+            candidates: session.candidates(&prior).len(),
+            suggestions: suggestions(&prior, session.history(), req.top),
+        })
+    }
+
+    /// Every word that fits `req.history`, most likely first.
+    pub fn candidates(&self, req: CandidatesRequest) -> Result<CandidatesResponse, ApiError> {
+        let implementation = implementation(req.implementation.as_deref())?;
+        let mut session = session(implementation, &self.tuning);
+        session
+            .set_history(&entries(&req.history))
+            .map_err(|e| ApiError::new(422, e))?;
+        let candidates = session.candidates(&prior(implementation, &self.tuning));
+        let count = candidates.len();
+        Ok(CandidatesResponse {
+            count,
+            candidates: candidates
+                .into_iter()
+                .take(req.limit.unwrap_or(usize::MAX))
+                .map(|(word, p)| Candidate { word, p })
+                .collect(),
+        })
+    }
+
+    /// Forgets the session `id`, returning whether there was one.
+    pub fn end(&mut self, id: &str) -> bool {
+        self.sessions.remove(id).is_some()
+    }
+
+    /// Answers `method` on `path` with a JSON body, parsing the request from `body`.
+    pub fn handle(&mut self, method: &Method, path: &str, body: &str) -> (u16, String) {
+        let result = match (method, path) {
+            (Method::Post, "/guess") => parse(body)
+                .and_then(|req| self.guess(req))
+                .map(|r| to_json(&r)),
+            (Method::Post, "/candidates") => parse(body)
+                .and_then(|req| self.candidates(req))
+                .map(|r| to_json(&r)),
+            (Method::Post, "/feedback") => parse(body).and_then(feedback),
+            (Method::Get, "/implementations") => Ok(implementations()),
+            (Method::Delete, p) if p.starts_with("/sessions/") => {
+                let id = &p["/sessions/".len()..];
+                if self.end(id) {
+                    Ok(json!({ "ended": id }).to_string())
+                } else {
+                    Err(ApiError::new(404, format!("no session '{}'", id)))
+                }
+            }
+            (_, "/guess" | "/candidates" | "/feedback" | "/implementations") => {
+                Err(ApiError::new(405, format!("{} doesn't take {}", path, method)))
+            }
+            _ => Err(ApiError::new(404, format!("no endpoint {}", path))),
+        };
+        match result {
+            Ok(body) => (200, body),
+            Err(e) => (e.status, json!({ "error": e.message }).to_string()),
+        }
+    }
+}
+
+/// The mask `req.guess` shows if the answer is `req.answer`.
+fn feedback(req: FeedbackRequest) -> Result<String, ApiError> {
+    for word in [&req.guess, &req.answer] {
+        if word.len() != 5 || !word.bytes().all(|b| b.is_ascii_lowercase()) {
+            return Err(ApiError::new(
+                400,
+                format!("'{}' is not five lowercase letters", word),
+            ));
+        }
+    }
+    let mask = Pattern::compute(&req.answer, &req.guess);
+    Ok(json!({ "mask": mask, "tiles": mask.to_string() }).to_string())
+}
+
+fn implementations() -> String {
+    let list: Vec<_> = Implementation::value_variants()
+        .iter()
+        .map(|i| {
+            json!({
+                "name": i.name(),
+                "deterministic": i.deterministic(),
+                "prior": i.prior().to_possible_value().map(|v| v.get_name()),
+            })
+        })
+        .collect();
+    json!({ "implementations": list }).to_string()
+}
+
+fn parse<'a, T: Deserialize<'a>>(body: &'a str) -> Result<T, ApiError> {
+    serde_json::from_str(body).map_err(|e| ApiError::new(400, e))
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).expect("responses serialise")
+}
+
+pub fn implementation(name: Option<&str>) -> Result<Implementation, ApiError> {
+    match name {
+        None => Ok(Implementation::Sigmoid),
+        Some(name) => Implementation::from_str(name, true).map_err(|_| {
+            ApiError::new(
+                400,
+                format!("no implementation '{}'; GET /implementations lists them", name),
+            )
+        }),
+    }
+}
+
+/// A session playing `implementation`, tuned like `tuning`.
+pub fn session(implementation: Implementation, tuning: &Tuning) -> GameSession {
+    let tuning = tuning.clone();
+    Session::new(Box::new(move || implementation.guesser(&tuning)))
+}
+
+/// The prior `tuning` picks for `implementation`.
+pub fn prior(implementation: Implementation, tuning: &Tuning) -> Box<dyn AnswerPrior> {
+    tuning.prior.unwrap_or(implementation.prior()).build()
+}
+
+fn entries(history: &[Entry]) -> Vec<(String, Pattern)> {
+    history.iter().map(|e| (e.word.clone(), e.mask)).collect()
+}
+
+/// Answers requests on `addr` until the process is stopped.
+pub fn serve(addr: &str) {
+    let server = Server::http(addr).unwrap_or_else(|e| {
+        eprintln!("can't listen on {}: {}", addr, e);
+        std::process::exit(1);
+    });
+    eprintln!("listening on http://{}", addr);
+    let content_type =
+        Header::from_bytes("Content-Type", "application/json").expect("the header is valid");
+
+    let mut solver = Solver::new();
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        let read = request
+            .as_reader()
+            .take(MAX_BODY + 1)
+            .read_to_string(&mut body);
+        let (status, response) = match read {
+            Err(e) => (400, json!({ "error": e.to_string() }).to_string()),
+            Ok(n) if n as u64 > MAX_BODY => {
+                (413, json!({ "error": "body too large" }).to_string())
+            }
+            Ok(_) => {
+                let path = request.url().split('?').next().unwrap_or("").to_string();
+                solver.handle(request.method(), &path, &body)
+            }
+        };
+        let response = Response::from_string(response)
+            .with_status_code(status)
+            .with_header(content_type.clone());
+        if let Err(e) = request.respond(response) {
+            eprintln!("can't respond: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn call(solver: &mut Solver, method: Method, path: &str, body: &str) -> (u16, Value) {
+        let (status, body) = solver.handle(&method, path, body);
+        (status, serde_json::from_str(&body).unwrap())
+    }
+
+    #[test]
+    fn endpoints() {
+        let mut solver = Solver::new();
+        let body = r#"{"guess": "tares", "answer": "cigar"}"#;
+        let (status, v) = call(&mut solver, Method::Post, "/feedback", body);
+        assert_eq!(status, 200);
+        assert_eq!(v["mask"], "BYYBB");
+
+        let (status, v) = call(&mut solver, Method::Get, "/implementations", "");
+        assert_eq!(status, 200);
+        assert_eq!(
+            v["implementations"].as_array().unwrap().len(),
+            Implementation::value_variants().len()
+        );
+
+        let history = r#"[{"word": "tares", "mask": "BYYBB"}, {"word": "drain", "mask": "BYYYB"}]"#;
+        let body = format!(r#"{{"history": {}, "implementation": "cutoff"}}"#, history);
+        let (status, v) = call(&mut solver, Method::Post, "/candidates", &body);
+        assert_eq!(status, 200);
+        let candidates = v["candidates"].as_array().unwrap();
+        assert!(candidates.iter().any(|c| c["word"] == "cigar"));
+
+        let body = format!(
+            r#"{{"history": {}, "implementation": "cutoff", "session": "a"}}"#,
+            history
+        );
+        let (status, v) = call(&mut solver, Method::Post, "/guess", &body);
+        assert_eq!(status, 200);
+        let suggestions = v["suggestions"].as_array().unwrap().len();
+        assert_eq!(suggestions, candidates.len().min(3));
+        assert_eq!(call(&mut solver, Method::Delete, "/sessions/a", "").0, 200);
+        assert_eq!(call(&mut solver, Method::Delete, "/sessions/a", "").0, 404);
+    }
+
+    #[test]
+    fn errors() {
+        let mut solver = Solver::new();
+        let mut status = |method, path, body| call(&mut solver, method, path, body).0;
+        assert_eq!(status(Method::Post, "/guess", "{"), 400);
+        assert_eq!(status(Method::Post, "/guess", r#"{"implementation": "magic"}"#), 400);
+        let short_mask = r#"{"history": [{"word": "tares", "mask": "BYYB"}]}"#;
+        assert_eq!(status(Method::Post, "/guess", short_mask), 400);
+        let not_a_word = r#"{"history": [{"word": "xxxxx", "mask": "BYYBB"}]}"#;
+        assert_eq!(status(Method::Post, "/guess", not_a_word), 422);
+        let upper_case = r#"{"guess": "TARES", "answer": "cigar"}"#;
+        assert_eq!(status(Method::Post, "/feedback", upper_case), 400);
+        assert_eq!(status(Method::Get, "/guess", ""), 405);
+        assert_eq!(status(Method::Get, "/nowhere", ""), 404);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::prior::{weigh_sorted, AnswerPrior};
+use crate::validate::{check_history, Contradiction};
+use crate::{dictionary, Guess, Guesser, NoCandidates, Pattern};
+use std::borrow::Cow;
+use std::fmt;
+
+/// A game against an answer the guesser can't see, such as one being played elsewhere, fed the
+/// mask of each guess as it is seen.
+///
+/// The guesser is only asked for a guess when one is wanted, and catches up on every mask fed
+/// since then first. If the history is replaced by one that doesn't extend it, a fresh guesser is
+/// built with `make`.
+pub struct Session<G, F> {
+    make: F,
+    guesser: G,
+    history: Vec<Guess<'static>>,
+    /// How much of the history the guesser has seen, and the guess it made then.
+    asked: Option<(usize, String)>,
+}
+
+/// Why a session can't take some feedback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionError {
+    NotAWord(String),
+    Contradiction(Contradiction),
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionError::NotAWord(word) => write!(f, "'{}' is not in the dictionary", word),
+            SessionError::Contradiction(c) => c.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+impl<G: Guesser, F: FnMut() -> G> Session<G, F> {
+    pub fn new(mut make: F) -> Self {
+        let guesser = make();
+        Self {
+            make,
+            guesser,
+            history: Vec::new(),
+            asked: None,
+        }
+    }
+
+    pub fn history(&self) -> &[Guess<'static>] {
+        &self.history
+    }
+
+    /// Records that guessing `word` showed `mask`. Fails, leaving the session as it was, if
+    /// `word` isn't in the dictionary or no dictionary word could have shown every mask so far.
+    pub fn feedback(&mut self, word: &str, mask: Pattern) -> Result<(), SessionError> {
+        let mut history = self.history.clone();
+        history.push(entry(word, mask)?);
+        check_history(&history).map_err(SessionError::Contradiction)?;
+        self.history = history;
+        Ok(())
+    }
+
+    /// Replaces the history with the guesses and masks of `entries`, checked as by `feedback`.
+    pub fn set_history(&mut self, entries: &[(String, Pattern)]) -> Result<(), SessionError> {
+        let history = entries
+            .iter()
+            .map(|(word, mask)| entry(word, *mask))
+            .collect::<Result<Vec<_>, _>>()?;
+        check_history(&history).map_err(SessionError::Contradiction)?;
+        let extends = history.len() >= self.history.len()
+            && self.history
+                .iter()
+                .zip(&history)
+                .all(|(a, b)| a.word == b.word && a.mask == b.mask);
+        if !extends {
+            self.reset();
+        }
+        self.history = history;
+        Ok(())
+    }
+
+    /// Starts a new game with a fresh guesser.
+    pub fn reset(&mut self) {
+        self.guesser = (self.make)();
+        self.history.clear();
+        self.asked = None;
+    }
+
+    /// The guesser's next guess after the history.
+    pub fn guess(&mut self) -> Result<String, NoCandidates> {
+        let len = self.history.len();
+        let from = match &self.asked {
+            Some((n, guess)) if *n == len => return Ok(guess.clone()),
+            Some((n, _)) => n + 1,
+            None => 0,
+        };
+        for n in from..len {
+            self.guesser.guess(&self.history[..n])?;
+        }
+        let guess = self.guesser.guess(&self.history)?;
+        self.asked = Some((len, guess.clone()));
+        Ok(guess)
+    }
+
+    /// Every dictionary word that fits the history, with the probability `prior` gives it of
+    /// being the answer, most likely first.
+    pub fn candidates<P: AnswerPrior + ?Sized>(&self, prior: &P) -> Vec<(&'static str, f64)> {
+        let mut remaining = weigh_sorted(prior);
+        remaining.retain(|&(word, _)| self.history.iter().all(|g| g.matches(word)));
+        let total: f64 = remaining.iter().map(|&(_, w)| w).sum();
+        for (_, w) in &mut remaining {
+            *w /= total;
+        }
+        remaining
+    }
+}
+
+/// A history entry for guessing `word`, if it is in the dictionary.
+fn entry(word: &str, mask: Pattern) -> Result<Guess<'static>, SessionError> {
+    let word = dictionary()
+        .iter()
+        .find(|&&(w, _)| w == word)
+        .ok_or_else(|| SessionError::NotAWord(word.to_string()))?
+        .0;
+    Ok(Guess {
+        word: Cow::Borrowed(word),
+        mask,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::Cutoff;
+    use crate::prior::RawFrequency;
+
+    #[test]
+    fn follows_feedback() {
+        let mut session = Session::new(Cutoff::new);
+        assert_eq!(session.guess().unwrap(), "tares");
+        session
+            .feedback("tares", Pattern::compute("cigar", "tares"))
+            .unwrap();
+        let second = session.guess().unwrap();
+        assert_eq!(session.guess().unwrap(), second);
+
+        // Setting the same history keeps the guesser, and a different one starts again.
+        let entries: Vec<_> = session
+            .history()
+            .iter()
+            .map(|g| (g.word.to_string(), g.mask))
+            .collect();
+        session.set_history(&entries).unwrap();
+        assert_eq!(session.guess().unwrap(), second);
+        let mut fresh = Cutoff::new();
+        fresh.guess(&[]).unwrap();
+        assert_eq!(Ok(second), fresh.guess(session.history()));
+
+        let candidates = session.candidates(&RawFrequency);
+        assert!(candidates.iter().any(|&(word, _)| word == "cigar"));
+        let total: f64 = candidates.iter().map(|&(_, p)| p).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rejects_bad_feedback() {
+        let mut session = Session::new(Cutoff::new);
+        assert_eq!(
+            session.feedback("xxxxx", Pattern::WIN),
+            Err(SessionError::NotAWord("xxxxx".to_string()))
+        );
+        session.feedback("cigar", "BBBBB".parse().unwrap()).unwrap();
+        assert!(matches!(
+            session.feedback("tares", "GGGGB".parse().unwrap()),
+            Err(SessionError::Contradiction(_))
+        ));
+        assert_eq!(session.history().len(), 1);
+    }
+}
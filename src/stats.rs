@@ -0,0 +1,254 @@
+use crate::trace::Trace;
+use std::fmt;
+
+/// How many of the hardest answers a `Report` keeps.
+pub const HARDEST: usize = 20;
+
+/// A summary of a benchmark run, from the trace of every game.
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// Games that were solved.
+    pub games: usize,
+    pub failures: usize,
+    /// `histogram[n]` is how many games took `n` guesses.
+    pub histogram: Vec<usize>,
+    /// The mean of the solved games, or `None` if none were solved.
+    pub mean: Option<f64>,
+    pub median: Option<f64>,
+    /// The sample standard deviation, or `None` with fewer than two solved games.
+    pub std_dev: Option<f64>,
+    /// Half the width of the 95% confidence interval of `mean`, or `None` with fewer than two
+    /// solved games.
+    pub ci95: Option<f64>,
+    pub max: usize,
+    /// The averages over every game that got to each turn, first turn first.
+    pub turns: Vec<TurnSummary>,
+    /// The entropy, in bits, of which word is the answer before the first guess.
+    pub answer_entropy: f64,
+    /// Up to `HARDEST` answers that took the most guesses, failures first.
+    pub hardest: Vec<(&'static str, Option<usize>)>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TurnSummary {
+    /// How many games got to this turn.
+    pub games: usize,
+    /// The average number of candidates left after the turn.
+    pub candidates: f64,
+    /// The average information, in bits, gained by the turn.
+    pub gained: f64,
+    /// The average information, in bits, the turn was expected to gain.
+    pub expected: f64,
+}
+
+impl Report {
+    /// Summarises `traces`. `answer_entropy` is the entropy of the prior the traces were made
+    /// with, which is how many bits the games needed to gain on average.
+    pub fn new(traces: &[Trace], answer_entropy: f64) -> Self {
+        let mut scores: Vec<usize> = traces
+            .iter()
+            .filter(|t| t.solved)
+            .map(|t| t.turns.len())
+            .collect();
+        scores.sort_unstable();
+        let games = scores.len();
+
+        let mut histogram = Vec::new();
+        for &s in &scores {
+            if s >= histogram.len() {
+                histogram.resize(s + 1, 0);
+            }
+            histogram[s] += 1;
+        }
+
+        let mean = (games > 0).then(|| scores.iter().sum::<usize>() as f64 / games as f64);
+        let median = match games {
+            0 => None,
+            n if n % 2 == 1 => Some(scores[n / 2] as f64),
+            n => Some((scores[n / 2 - 1] + scores[n / 2]) as f64 / 2.0),
+        };
+        // The sample standard deviation, so that the confidence interval isn't too narrow for
+        // short runs. A single game has no spread to measure.
+        let std_dev = mean.filter(|_| games > 1).map(|mean| {
+            (scores
+                .iter()
+                .map(|&s| (s as f64 - mean).powi(2))
+                .sum::<f64>()
+                / (games as f64 - 1.0))
+                .sqrt()
+        });
+        let ci95 = std_dev.map(|sd| 1.96 * sd / (games as f64).sqrt());
+
+        let mut turns: Vec<TurnSummary> = Vec::new();
+        for trace in traces {
+            for (i, turn) in trace.turns.iter().enumerate() {
+                if i == turns.len() {
+                    turns.push(TurnSummary::default());
+                }
+                let t = &mut turns[i];
+                t.games += 1;
+                t.candidates += turn.after as f64;
+                t.gained += turn.gained;
+                t.expected += turn.expected;
+            }
+        }
+        for t in &mut turns {
+            t.candidates /= t.games as f64;
+            t.gained /= t.games as f64;
+            t.expected /= t.games as f64;
+        }
+
+        let mut hardest: Vec<_> = traces
+            .iter()
+            .map(|t| (t.answer, t.solved.then_some(t.turns.len())))
+            .collect();
+        // Failures count as the hardest of all.
+        hardest.sort_by_key(|&(_, s)| std::cmp::Reverse(s.unwrap_or(usize::MAX)));
+        hardest.truncate(HARDEST);
+
+        Self {
+            games,
+            failures: traces.len() - games,
+            histogram,
+            mean,
+            median,
+            std_dev,
+            ci95,
+            max: scores.last().copied().unwrap_or(0),
+            turns,
+            answer_entropy,
+            hardest,
+        }
+    }
+
+    /// The average information, in bits, gained over a whole game.
+    pub fn bits_per_game(&self) -> f64 {
+        self.turns.iter().map(|t| t.gained * t.games as f64).sum::<f64>()
+            / (self.games + self.failures) as f64
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sum: usize = self.histogram.iter().sum();
+        for (score, &count) in self.histogram.iter().enumerate().skip(1) {
+            let frac = count as f64 / sum as f64;
+            let w1 = (30.0 * frac).round() as usize;
+            let w2 = (30.0 * (1.0 - frac)).round() as usize;
+            writeln!(f, "{:>2}: {}{} ({})", score, "#".repeat(w1), " ".repeat(w2), count)?;
+        }
+        match (self.mean, self.median) {
+            (Some(mean), Some(median)) => {
+                write!(f, "mean {:.4}", mean)?;
+                if let Some(ci95) = self.ci95 {
+                    write!(f, " ± {:.4} (95% CI)", ci95)?;
+                }
+                write!(f, ", median {}", median)?;
+                if let Some(std_dev) = self.std_dev {
+                    write!(f, ", std dev {:.4}", std_dev)?;
+                }
+                writeln!(f, ", max {}, {} failed", self.max, self.failures)?;
+            }
+            _ => writeln!(f, "no games solved, {} failed", self.failures)?,
+        }
+
+        writeln!(f, "turn  games  candidates left  bits gained  bits expected")?;
+        for (i, t) in self.turns.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>4}  {:>5}  {:>15.1}  {:>11.2}  {:>13.2}",
+                i + 1,
+                t.games,
+                t.candidates,
+                t.gained,
+                t.expected
+            )?;
+        }
+        writeln!(
+            f,
+            "{:.2} bits gained per game, of {:.2} bits of answer entropy",
+            self.bits_per_game(),
+            self.answer_entropy
+        )?;
+
+        write!(f, "hardest:")?;
+        for &(answer, score) in &self.hardest {
+            match score {
+                Some(s) => write!(f, " {} ({})", answer, s)?,
+                None => write!(f, " {} (failed)", answer)?,
+            }
+        }
+        writeln!(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace::Turn;
+
+    fn trace(answer: &'static str, guesses: usize, solved: bool) -> Trace {
+        let turn = Turn {
+            guess: String::new(),
+            mask: crate::Pattern::compute("abcde", "fghij"),
+            before: 10,
+            after: 5,
+            expected: 1.5,
+            gained: 1.0,
+            alternatives: Vec::new(),
+        };
+        Trace {
+            answer,
+            turns: vec![turn; guesses],
+            solved,
+        }
+    }
+
+    #[test]
+    fn summary() {
+        let traces = [
+            trace("cigar", 3, true),
+            trace("rebut", 4, true),
+            trace("sissy", 32, false),
+            trace("humph", 6, true),
+            trace("awake", 3, true),
+        ];
+        let report = Report::new(&traces, 8.0);
+        assert_eq!(report.games, 4);
+        assert_eq!(report.failures, 1);
+        assert_eq!(report.histogram, [0, 0, 0, 2, 1, 0, 1]);
+        assert_eq!(report.mean, Some(4.0));
+        assert_eq!(report.median, Some(3.5));
+        assert_eq!(report.std_dev, Some(2f64.sqrt()));
+        assert_eq!(report.max, 6);
+        assert_eq!(report.turns.len(), 32);
+        assert_eq!(report.turns[3].games, 3);
+        assert_eq!(report.turns[6].games, 1);
+        assert_eq!(report.turns[0].candidates, 5.0);
+        assert_eq!(
+            report.hardest,
+            [
+                ("sissy", None),
+                ("humph", Some(6)),
+                ("rebut", Some(4)),
+                ("cigar", Some(3)),
+                ("awake", Some(3))
+            ]
+        );
+        assert_eq!(report.bits_per_game(), (3 + 4 + 32 + 6 + 3) as f64 / 5.0);
+    }
+
+    #[test]
+    fn too_few_games() {
+        let one = Report::new(&[trace("cigar", 4, true), trace("sissy", 6, false)], 8.0);
+        assert_eq!((one.mean, one.median), (Some(4.0), Some(4.0)));
+        assert_eq!((one.std_dev, one.ci95), (None, None));
+        let text = one.to_string();
+        assert!(text.contains("mean 4.0000, median 4, max 4, 1 failed"), "{}", text);
+
+        let none = Report::new(&[trace("sissy", 6, false)], 8.0);
+        assert_eq!((none.mean, none.median, none.std_dev), (None, None, None));
+        assert!(none.to_string().contains("no games solved, 1 failed"));
+        assert!(!none.to_string().contains("NaN"));
+    }
+}
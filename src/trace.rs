@@ -0,0 +1,204 @@
+use crate::entropy::{expected_guesses, guess_entropy, remaining_entropy};
+use crate::prior::{weigh, AnswerPrior};
+use crate::{Guess, Guesser, NoCandidates, PackedWord, Pattern, Wordle, MAX_MASK_ENUM};
+use serde::Serialize;
+use std::borrow::Cow;
+
+/// A turn-by-turn account of one game, as seen by a player who weighs the dictionary with some
+/// prior.
+#[derive(Debug, Clone)]
+pub struct Trace {
+    pub answer: &'static str,
+    pub turns: Vec<Turn>,
+    /// Whether the guesser found the answer within the round limit of `Wordle::play`.
+    pub solved: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Turn {
+    pub guess: String,
+    pub mask: Pattern,
+    /// How many words were still possible before the guess.
+    pub before: usize,
+    /// How many words are still possible after seeing `mask`.
+    pub after: usize,
+    /// The information, in bits, that the guess was expected to yield.
+    pub expected: f64,
+    /// The information, in bits, that `mask` actually yielded.
+    pub gained: f64,
+    /// The best candidates by expected number of guesses, best first.
+    pub alternatives: Vec<Alternative>,
+}
+
+/// A candidate the guesser could have played instead.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Alternative {
+    pub word: &'static str,
+    /// The probability that `word` is the answer.
+    pub p: f64,
+    /// The information, in bits, that `word` is expected to yield.
+    pub entropy: f64,
+    /// The expected number of guesses to finish the game from here, `word` included.
+    pub expected_guesses: f64,
+}
+
+impl Wordle {
+    /// The word in the guess pool equal to `word`, if there is one.
+    pub fn word(&self, word: &str) -> Option<&'static str> {
+        self.dict.get(word).copied()
+    }
+
+    /// Plays `ans` like `play`, but records every turn, measuring information against the
+    /// dictionary weighted by `prior` and listing the `alternatives` best candidates at each.
+    /// Fails if the guesser runs out of candidates.
+    pub fn trace<G: Guesser, P: AnswerPrior + ?Sized>(
+        &self,
+        ans: &'static str,
+        mut guesser: G,
+        prior: &P,
+        alternatives: usize,
+    ) -> Result<Trace, NoCandidates> {
+        let mut remaining = weigh(prior);
+        let mut hist = Vec::new();
+        let mut turns = Vec::new();
+        for _ in 1..=32 {
+            let guess = guesser.guess(&hist)?;
+            assert!(self.dict.contains(&*guess), "guess '{}' is not in the dict", guess);
+            let mask = Pattern::compute(ans, &guess);
+
+            let answers: Vec<_> = remaining
+                .iter()
+                .map(|&(word, w)| (PackedWord::new(word), w))
+                .collect();
+            let total: f64 = remaining.iter().map(|&(_, w)| w).sum();
+            let expected = guess_entropy(PackedWord::new(&guess), &answers, total);
+            let before = remaining.len();
+            let alternatives = best_candidates(&remaining, &answers, total, alternatives);
+
+            let g = Guess {
+                word: Cow::Borrowed(&*guess),
+                mask,
+            };
+            remaining.retain(|&(word, _)| g.matches(word));
+            let left: f64 = remaining.iter().map(|&(_, w)| w).sum();
+            turns.push(Turn {
+                guess: guess.clone(),
+                mask,
+                before,
+                after: remaining.len(),
+                expected,
+                gained: (total / left).log2(),
+                alternatives,
+            });
+
+            if guess == ans {
+                return Ok(Trace {
+                    answer: ans,
+                    turns,
+                    solved: true,
+                });
+            }
+            hist.push(Guess {
+                word: Cow::Owned(guess),
+                mask,
+            });
+        }
+        Ok(Trace {
+            answer: ans,
+            turns,
+            solved: false,
+        })
+    }
+}
+
+/// The `n` candidates left after `hist` that are expected to finish the game soonest, with the
+/// dictionary weighted by `prior`.
+pub fn suggestions<P: AnswerPrior + ?Sized>(prior: &P, hist: &[Guess], n: usize) -> Vec<Alternative> {
+    let mut remaining = weigh(prior);
+    remaining.retain(|&(word, _)| hist.iter().all(|g| g.matches(word)));
+    let answers: Vec<_> = remaining
+        .iter()
+        .map(|&(word, w)| (PackedWord::new(word), w))
+        .collect();
+    let total: f64 = remaining.iter().map(|&(_, w)| w).sum();
+    best_candidates(&remaining, &answers, total, n)
+}
+
+/// The `n` words of `remaining` that are expected to finish the game soonest.
+fn best_candidates(
+    remaining: &[(&'static str, f64)],
+    answers: &[(PackedWord, f64)],
+    total: f64,
+    n: usize,
+) -> Vec<Alternative> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let entropy = remaining_entropy(remaining.iter().map(|&(_, w)| w));
+    let max_entropy = entropy.min((remaining.len().min(MAX_MASK_ENUM) as f64).log2());
+    let mut order: Vec<_> = (0..remaining.len()).collect();
+    order.sort_by(|&a, &b| remaining[b].1.total_cmp(&remaining[a].1));
+
+    let mut best: Vec<Alternative> = Vec::with_capacity(n + 1);
+    for i in order {
+        let (word, w) = remaining[i];
+        let p = w / total;
+        // Less likely words can only do better by yielding more information, and none can yield
+        // more than is left to know or than it takes to tell every mask apart.
+        let bound = expected_guesses(p, max_entropy, entropy);
+        if best.len() == n && bound >= best[n - 1].expected_guesses {
+            break;
+        }
+        let h = guess_entropy(answers[i].0, answers, total);
+        let alt = Alternative {
+            word,
+            p,
+            entropy: h,
+            expected_guesses: expected_guesses(p, h, entropy),
+        };
+        let at = best.partition_point(|b| b.expected_guesses <= alt.expected_guesses);
+        best.insert(at, alt);
+        best.truncate(n);
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prior::RawFrequency;
+
+    #[test]
+    fn information_adds_up() {
+        let w = Wordle::new();
+        let trace = w
+            .trace("cigar", crate::algorithms::Cutoff::new(), &RawFrequency, 0)
+            .unwrap();
+        assert!(trace.solved);
+        assert_eq!(trace.turns.last().unwrap().guess, "cigar");
+        assert_eq!(trace.turns.last().unwrap().after, 1);
+        for pair in trace.turns.windows(2) {
+            assert_eq!(pair[0].after, pair[1].before);
+        }
+
+        // Every bit gained narrows the weighted candidates down until only the answer is left.
+        let weights = weigh(&RawFrequency);
+        let total: f64 = weights.iter().map(|&(_, w)| w).sum();
+        let answer = weights.iter().find(|&&(word, _)| word == "cigar").unwrap().1;
+        let gained: f64 = trace.turns.iter().map(|t| t.gained).sum();
+        assert!((gained - (total / answer).log2()).abs() < 1e-9, "{}", gained);
+    }
+
+    #[test]
+    fn guessing_the_likeliest_word_is_best_when_two_are_left() {
+        let remaining = [("fight", 3.0), ("light", 1.0)];
+        let answers: Vec<_> = remaining
+            .iter()
+            .map(|&(word, w)| (PackedWord::new(word), w))
+            .collect();
+        let best = best_candidates(&remaining, &answers, 4.0, 5);
+        assert_eq!(best.len(), 2);
+        assert_eq!(best[0].word, "fight");
+        assert_eq!(best[0].p, 0.75);
+    }
+}
@@ -0,0 +1,288 @@
+use crate::{Guesser, NoCandidates, Pattern, Wordle};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+
+/// The whole policy of a deterministic guesser, merged from the games it played.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Tree {
+    /// Games that were solved.
+    pub games: usize,
+    pub failures: usize,
+    /// `histogram[n]` is how many games took `n` guesses.
+    pub histogram: Vec<usize>,
+    /// The opening guess, or `None` before any game is added.
+    pub root: Option<Node>,
+}
+
+/// A guess, and what the guesser plays next for every pattern it showed.
+#[derive(Debug, Clone, Serialize)]
+pub struct Node {
+    pub guess: String,
+    /// How many answers reach this guess.
+    pub answers: usize,
+    /// Whether `guess` is one of those answers.
+    pub solves: bool,
+    /// How many of those answers are never found.
+    pub failures: usize,
+    /// The average number of guesses, this and earlier ones included, taken to solve those
+    /// answers that are found.
+    pub mean_guesses: f64,
+    pub max_guesses: usize,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub children: BTreeMap<Pattern, Node>,
+    #[serde(skip)]
+    total_guesses: usize,
+}
+
+/// Why a tree can't be built from some games.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TreeError {
+    /// The guesser ran out of candidates while guessing `answer`.
+    NoCandidates { answer: String },
+    /// Two games with the same guesses and patterns so far went on with different guesses, so
+    /// the guesser isn't deterministic.
+    Diverged {
+        path: Vec<(String, Pattern)>,
+        expected: String,
+        found: String,
+    },
+}
+
+impl fmt::Display for TreeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TreeError::NoCandidates { answer } => {
+                write!(f, "{} while guessing '{}'", NoCandidates, answer)
+            }
+            TreeError::Diverged {
+                path,
+                expected,
+                found,
+            } => {
+                write!(f, "the guesser played both '{}' and '{}'", expected, found)?;
+                if !path.is_empty() {
+                    write!(f, " after")?;
+                    for (guess, mask) in path {
+                        write!(f, " {} {:#}", guess, mask)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for TreeError {}
+
+impl Node {
+    fn new(guess: &str) -> Self {
+        Self {
+            guess: guess.to_string(),
+            answers: 0,
+            solves: false,
+            failures: 0,
+            mean_guesses: f64::NAN,
+            max_guesses: 0,
+            children: BTreeMap::new(),
+            total_guesses: 0,
+        }
+    }
+
+    /// How many nodes deep the subtree goes, this one included.
+    pub fn depth(&self) -> usize {
+        1 + self.children.values().map(Node::depth).max().unwrap_or(0)
+    }
+
+    /// How many guesses the subtree holds, this one included.
+    pub fn size(&self) -> usize {
+        1 + self.children.values().map(Node::size).sum::<usize>()
+    }
+}
+
+impl Tree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the game in which `guesses` were made looking for `answer`. Fails without changing
+    /// the tree if the game doesn't follow the policy of those added before.
+    pub fn add(&mut self, answer: &str, guesses: &[String]) -> Result<(), TreeError> {
+        let solved = guesses.last().is_some_and(|g| g == answer);
+        self.check(answer, guesses)?;
+
+        if solved {
+            self.games += 1;
+            if guesses.len() >= self.histogram.len() {
+                self.histogram.resize(guesses.len() + 1, 0);
+            }
+            self.histogram[guesses.len()] += 1;
+        } else {
+            self.failures += 1;
+        }
+
+        let Some(first) = guesses.first() else {
+            return Ok(());
+        };
+        let mut node = self.root.get_or_insert_with(|| Node::new(first));
+        for (i, guess) in guesses.iter().enumerate() {
+            node.answers += 1;
+            if solved {
+                node.total_guesses += guesses.len();
+                node.max_guesses = node.max_guesses.max(guesses.len());
+                node.mean_guesses =
+                    node.total_guesses as f64 / (node.answers - node.failures) as f64;
+            } else {
+                node.failures += 1;
+            }
+            let Some(next) = guesses.get(i + 1) else {
+                node.solves |= solved;
+                break;
+            };
+            node = node
+                .children
+                .entry(Pattern::compute(answer, guess))
+                .or_insert_with(|| Node::new(next));
+        }
+        Ok(())
+    }
+
+    /// Checks that the game follows the policy so far.
+    fn check(&self, answer: &str, guesses: &[String]) -> Result<(), TreeError> {
+        let mut path = Vec::new();
+        let mut node = self.root.as_ref();
+        for guess in guesses {
+            let Some(n) = node else {
+                break;
+            };
+            if n.guess != *guess {
+                return Err(TreeError::Diverged {
+                    path,
+                    expected: n.guess.clone(),
+                    found: guess.clone(),
+                });
+            }
+            let mask = Pattern::compute(answer, guess);
+            node = n.children.get(&mask);
+            path.push((guess.clone(), mask));
+        }
+        Ok(())
+    }
+
+    /// The average number of guesses taken over the solved games.
+    pub fn mean(&self) -> f64 {
+        self.root.as_ref().map_or(f64::NAN, |n| n.mean_guesses)
+    }
+
+    /// Writes the tree as a Graphviz digraph, with an edge for every pattern labelled with its
+    /// letters. Guesses that are answers have a double border.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph policy {\n");
+        out.push_str("    node [shape=box, fontname=\"monospace\"];\n");
+        if let Some(root) = &self.root {
+            let mut next = 0;
+            write_dot(root, &mut next, &mut out);
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Writes `node` and its subtree as DOT, numbering nodes from `next`, and returns the id of
+/// `node`.
+fn write_dot(node: &Node, next: &mut usize, out: &mut String) -> usize {
+    let id = *next;
+    *next += 1;
+    let plural = if node.answers == 1 { "" } else { "s" };
+    let mut label = format!("{}\\n{} answer{}", node.guess, node.answers, plural);
+    if node.answers > node.failures {
+        write!(label, ", {:.2} avg, {} max", node.mean_guesses, node.max_guesses).unwrap();
+    }
+    if node.failures > 0 {
+        write!(label, ", {} failed", node.failures).unwrap();
+    }
+    let peripheries = if node.solves { 2 } else { 1 };
+    writeln!(
+        out,
+        "    n{} [label=\"{}\", peripheries={}];",
+        id, label, peripheries
+    )
+    .unwrap();
+    for (mask, child) in &node.children {
+        let child_id = write_dot(child, next, out);
+        writeln!(out, "    n{} -> n{} [label=\"{:#}\"];", id, child_id, mask).unwrap();
+    }
+    id
+}
+
+impl Wordle {
+    /// Plays every answer with a guesser from `make` and merges the games into a tree.
+    pub fn tree<G: Guesser>(
+        &self,
+        answers: &[&'static str],
+        mut make: impl FnMut() -> G,
+    ) -> Result<Tree, TreeError> {
+        let mut tree = Tree::new();
+        for &answer in answers {
+            let guesses = self.guesses(answer, make()).map_err(|_| TreeError::NoCandidates {
+                answer: answer.to_string(),
+            })?;
+            tree.add(answer, &guesses)?;
+        }
+        Ok(tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(guesses: &[&str]) -> Vec<String> {
+        guesses.iter().map(|g| g.to_string()).collect()
+    }
+
+    #[test]
+    fn merges_games() {
+        let w = Wordle::new();
+        let answers = ["cigar", "rebut", "sissy", "humph", "awake", "blush"];
+        let tree = w.tree(&answers, crate::algorithms::Cutoff::new).unwrap();
+        let root = tree.root.as_ref().unwrap();
+        assert_eq!(root.guess, "tares");
+        assert_eq!(root.answers, answers.len());
+        assert_eq!(tree.games + tree.failures, answers.len());
+        assert_eq!(tree.histogram.iter().sum::<usize>(), tree.games);
+        assert_eq!(root.depth(), root.max_guesses);
+
+        // Every answer either stops at a node or goes on to one of its children.
+        fn check(node: &Node) {
+            let below: usize = node.children.values().map(|c| c.answers).sum();
+            assert_eq!(below + node.solves as usize, node.answers, "{}", node.guess);
+            node.children.values().for_each(check);
+        }
+        check(root);
+
+        let dot = tree.to_dot();
+        assert!(dot.starts_with("digraph policy {"));
+        assert_eq!(dot.matches(" -> ").count(), root.size() - 1);
+        let json = serde_json::to_string(&tree).unwrap();
+        assert!(json.contains("\"guess\":\"tares\""));
+    }
+
+    #[test]
+    fn rejects_a_different_policy() {
+        let mut tree = Tree::new();
+        tree.add("cigar", &game(&["tares", "drain", "cigar"])).unwrap();
+        // `rebut` shows a different pattern for `tares`, so it may be followed by anything.
+        tree.add("rebut", &game(&["tares", "brute", "rebut"])).unwrap();
+        let error = tree
+            .add("cigar", &game(&["tares", "cigar"]))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "the guesser played both 'drain' and 'cigar' after tares BYYBB"
+        );
+        assert_eq!(tree.games, 2);
+        assert_eq!(tree.histogram, [0, 0, 0, 2]);
+        assert_eq!(tree.mean(), 3.0);
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{dictionary, Correctness, Guess, PackedWord, Pattern};
+use std::fmt;
+
+/// Whether some answer, in or out of the dictionary, shows `mask` when `guess` is guessed.
+///
+/// Not every pattern can be seen for every guess: Wordle marks repeated letters yellow from left
+/// to right, so a grey copy of a letter can't come before a yellow one, and there is only room
+/// for so many misplaced letters. Letters that aren't in the guess all produce the same marks, so
+/// it is enough to try every answer made of the guess's letters and one other.
+///
+/// Only which letters repeat matters, so the guess may be written in any alphabet. Panics if it
+/// isn't five letters.
+pub fn is_possible(guess: &str, mask: Pattern) -> bool {
+    // Number the guess's letters from `a` in order of appearance, leaving the next for the filler.
+    let mut seen: Vec<char> = Vec::with_capacity(5);
+    let mut bytes = [0; 5];
+    let mut chars = guess.chars();
+    for b in &mut bytes {
+        let c = chars
+            .next()
+            .unwrap_or_else(|| panic!("'{}' is not a five-letter word", guess));
+        let i = seen.iter().position(|&s| s == c).unwrap_or_else(|| {
+            seen.push(c);
+            seen.len() - 1
+        });
+        *b = b'a' + i as u8;
+    }
+    assert!(chars.next().is_none(), "'{}' is not a five-letter word", guess);
+    let guess = PackedWord::from_bytes(bytes);
+    let letters: Vec<u8> = (b'a'..=b'a' + seen.len() as u8).collect();
+
+    let n = letters.len();
+    (0..n.pow(5)).any(|mut i| {
+        let mut answer = [0; 5];
+        for slot in &mut answer {
+            *slot = letters[i % n];
+            i /= n;
+        }
+        guess.compute(PackedWord::from_bytes(answer)) == mask.index()
+    })
+}
+
+/// Why a history of guesses can't have come from a game with a dictionary answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Contradiction {
+    /// No answer at all shows entry `entry`'s mask for its word.
+    Impossible { entry: usize },
+    /// Every entry is possible on its own, but no dictionary word fits entries up to and
+    /// including `entry`.
+    NoWordLeft { entry: usize },
+}
+
+impl Contradiction {
+    /// The index of the first entry that can't be reconciled with those before it.
+    pub fn entry(self) -> usize {
+        match self {
+            Contradiction::Impossible { entry } | Contradiction::NoWordLeft { entry } => entry,
+        }
+    }
+}
+
+impl fmt::Display for Contradiction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Contradiction::Impossible { entry } => {
+                write!(f, "guess {}: no answer shows that mask", entry + 1)
+            }
+            Contradiction::NoWordLeft { entry } => write!(
+                f,
+                "guess {}: no dictionary word fits it and the guesses before it",
+                entry + 1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Contradiction {}
+
+/// Checks that some dictionary word could have produced every mask in `hist`, reporting the
+/// first entry that makes the history contradictory if not.
+pub fn check_history(hist: &[Guess]) -> Result<(), Contradiction> {
+    let mut remaining: Vec<&'static str> = dictionary().iter().map(|&(word, _)| word).collect();
+    for (entry, g) in hist.iter().enumerate() {
+        if !is_possible(&g.word, g.mask) {
+            return Err(Contradiction::Impossible { entry });
+        }
+        remaining.retain(|word| g.matches(word));
+        if remaining.is_empty() {
+            return Err(Contradiction::NoWordLeft { entry });
+        }
+    }
+    Ok(())
+}
+
+/// How a guess breaks hard mode, in which every hint revealed so far must be used: green
+/// letters stay where they are and yellow ones must be played again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HardModeViolation {
+    /// Position `position`, counting from 0, was shown to be `letter`.
+    Green { position: usize, letter: char },
+    /// An earlier guess showed the answer has at least `count` of `letter`.
+    Missing { letter: char, count: usize },
+}
+
+impl fmt::Display for HardModeViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            HardModeViolation::Green { position, letter } => {
+                let ordinal = ["1st", "2nd", "3rd", "4th", "5th"][position];
+                write!(f, "{} letter must be {}", ordinal, letter.to_uppercase())
+            }
+            HardModeViolation::Missing { letter, count: 1 } => {
+                write!(f, "guess must contain {}", letter.to_uppercase())
+            }
+            HardModeViolation::Missing { letter, count } => {
+                write!(f, "guess must contain {} {}s", count, letter.to_uppercase())
+            }
+        }
+    }
+}
+
+impl std::error::Error for HardModeViolation {}
+
+/// Checks that `guess` uses every hint in `hist`, as hard mode requires, reporting the first one
+/// it ignores. Letters are compared as `char`s, so words may be in any alphabet.
+pub fn check_hard_mode(hist: &[Guess], guess: &str) -> Result<(), HardModeViolation> {
+    let guess: Vec<char> = guess.chars().collect();
+    for g in hist {
+        let word: Vec<char> = g.word.chars().collect();
+        let marks = g.mask.to_array();
+        for (position, &c) in marks.iter().enumerate() {
+            let letter = word[position];
+            if c == Correctness::Correct && guess.get(position) != Some(&letter) {
+                return Err(HardModeViolation::Green { position, letter });
+            }
+        }
+        for (i, &letter) in word.iter().enumerate() {
+            let shown = |l: &char, c: &Correctness| *l == letter && *c != Correctness::Incorrect;
+            let count = word.iter().zip(&marks).filter(|(l, c)| shown(l, c)).count();
+            let first = word.iter().position(|&l| l == letter) == Some(i);
+            if first && guess.iter().filter(|&&l| l == letter).count() < count {
+                return Err(HardModeViolation::Missing { letter, count });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+
+    fn guess(word: &'static str, mask: &str) -> Guess<'static> {
+        Guess {
+            word: Cow::Borrowed(word),
+            mask: mask.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn repeated_letters() {
+        // The first misplaced copy of a letter is the one marked yellow.
+        assert!(is_possible("speed", "BBYBB".parse().unwrap()));
+        assert!(!is_possible("speed", "BBBYB".parse().unwrap()));
+        assert!(is_possible("speed", "BBYYB".parse().unwrap()));
+        // One green `e` and one yellow needs an answer with two.
+        assert!(is_possible("speed", "BBGYB".parse().unwrap()));
+    }
+
+    #[test]
+    fn too_many_misplaced() {
+        // Five misplaced letters need a derangement, which `aaaab` can't have.
+        assert!(is_possible("abcde", "YYYYY".parse().unwrap()));
+        assert!(!is_possible("aaaab", "YYYYY".parse().unwrap()));
+        // Four greens leave a single letter, which can't be anywhere else.
+        assert!(!is_possible("abcde", "GGGGY".parse().unwrap()));
+    }
+
+    #[test]
+    fn every_computed_mask_is_possible() {
+        for answer in ["cigar", "rebut", "sissy", "humph", "awake"] {
+            for g in ["tares", "speed", "eerie", "mamma"] {
+                assert!(is_possible(g, Pattern::compute(answer, g)), "{} {}", answer, g);
+            }
+        }
+    }
+
+    #[test]
+    fn history() {
+        let ok = [guess("tares", "BYYBB"), guess("drain", "BYYYB")];
+        assert_eq!(check_history(&ok), Ok(()));
+
+        let impossible = [guess("tares", "BYYBB"), guess("speed", "BBBYB")];
+        assert_eq!(
+            check_history(&impossible),
+            Err(Contradiction::Impossible { entry: 1 })
+        );
+
+        // `cigar` all grey rules out the `a` and `r` that `tares` all green needs.
+        let contradictory = [
+            guess("cigar", "BBBBB"),
+            guess("drain", "BBBBB"),
+            guess("tares", "GGGGG"),
+        ];
+        assert_eq!(
+            check_history(&contradictory),
+            Err(Contradiction::NoWordLeft { entry: 2 })
+        );
+        assert_eq!(check_history(&contradictory).unwrap_err().entry(), 2);
+    }
+
+    #[test]
+    fn hard_mode() {
+        let hist = [guess("tares", "BYYBB")];
+        assert_eq!(check_hard_mode(&hist, "drain"), Ok(()));
+        assert_eq!(
+            check_hard_mode(&hist, "crone"),
+            Err(HardModeViolation::Missing { letter: 'a', count: 1 })
+        );
+        let hist = [guess("tares", "BYYBB"), guess("cigar", "GBBYY")];
+        assert_eq!(check_hard_mode(&hist, "carry"), Ok(()));
+        let violation = check_hard_mode(&hist, "rajah").unwrap_err();
+        assert_eq!(violation.to_string(), "1st letter must be C");
+
+        // Two yellow `e`s need two `e`s.
+        let hist = [guess("eerie", "YYBBB")];
+        assert_eq!(check_hard_mode(&hist, "emcee"), Ok(()));
+        let violation = check_hard_mode(&hist, "bench").unwrap_err();
+        assert_eq!(violation.to_string(), "guess must contain 2 Es");
+    }
+
+    #[test]
+    fn letters_not_bytes() {
+        // `ñ` is two bytes, but one letter like any other.
+        assert!(is_possible("señor", Pattern::compute("niños", "señor")));
+        assert!(!is_possible("señor", "GGGGY".parse().unwrap()));
+        assert!(is_possible("ññññn", "YBBBY".parse().unwrap()));
+
+        let hist = [guess("señor", "YBGGB")];
+        // No English word has an `ñ`, but that is a contradiction, not a crash.
+        assert_eq!(check_history(&hist), Err(Contradiction::NoWordLeft { entry: 0 }));
+        assert_eq!(check_hard_mode(&hist, "niños"), Ok(()));
+        let violation = check_hard_mode(&hist, "manos").unwrap_err();
+        assert_eq!(violation.to_string(), "3rd letter must be Ñ");
+    }
+}
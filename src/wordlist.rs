@@ -0,0 +1,395 @@
+use crate::entropy::remaining_entropy;
+use crate::prior::AnswerPrior;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// A word list in the format of the built-in dictionary: a word and its frequency count on
+/// each line, in file order.
+pub type WordList = Vec<(String, usize)>;
+
+/// Something wrong with a word list, found by `validate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// The line isn't a word, a space and a count.
+    BadLine { line: usize, text: String },
+    /// The word was already on line `first`.
+    Duplicate { line: usize, word: String, first: usize },
+    WrongLength { line: usize, word: String },
+    NotLowercase { line: usize, word: String },
+    /// A word on the answer list isn't in the word list.
+    MissingAnswer { word: String },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::BadLine { line, text } => {
+                write!(f, "line {}: '{}' is not a word and a frequency", line, text)
+            }
+            Problem::Duplicate { line, word, first } => {
+                write!(f, "line {}: '{}' is already on line {}", line, word, first)
+            }
+            Problem::WrongLength { line, word } => {
+                write!(f, "line {}: '{}' is not five letters", line, word)
+            }
+            Problem::NotLowercase { line, word } => {
+                write!(f, "line {}: '{}' is not all lowercase letters", line, word)
+            }
+            Problem::MissingAnswer { word } => {
+                write!(f, "the answer '{}' is not in the list", word)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Problem {}
+
+/// Every problem with `list`, in the order they are found, and the answers of `answers`, a
+/// whitespace-separated list, that it lacks.
+pub fn validate(list: &str, answers: Option<&str>) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    for (i, text) in list.lines().enumerate() {
+        let line = i + 1;
+        let Some((word, _)) = split_line(text) else {
+            problems.push(Problem::BadLine {
+                line,
+                text: text.to_string(),
+            });
+            continue;
+        };
+        if let Some(&first) = seen.get(word) {
+            problems.push(Problem::Duplicate {
+                line,
+                word: word.to_string(),
+                first,
+            });
+            continue;
+        }
+        seen.insert(word, line);
+        if word.chars().count() != 5 {
+            problems.push(Problem::WrongLength {
+                line,
+                word: word.to_string(),
+            });
+        } else if !word.chars().all(char::is_lowercase) {
+            problems.push(Problem::NotLowercase {
+                line,
+                word: word.to_string(),
+            });
+        }
+    }
+    for answer in answers.unwrap_or_default().split_whitespace() {
+        if !seen.contains_key(answer) {
+            problems.push(Problem::MissingAnswer {
+                word: answer.to_string(),
+            });
+        }
+    }
+    problems
+}
+
+/// The word and count of a line, if it is a word, a single space and a number.
+fn split_line(line: &str) -> Option<(&str, usize)> {
+    let (word, count) = line.split_once(' ')?;
+    if word.is_empty() || word.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((word, count.parse().ok()?))
+}
+
+/// Reads a word list, failing with its first problem.
+pub fn parse(list: &str) -> Result<WordList, Problem> {
+    if let Some(problem) = validate(list, None).into_iter().next() {
+        return Err(problem);
+    }
+    Ok(list
+        .lines()
+        .map(|line| {
+            let (word, count) = split_line(line).expect("validated");
+            (word.to_string(), count)
+        })
+        .collect())
+}
+
+/// Writes a word list so that `parse` reads it back.
+pub fn format(list: &[(String, usize)]) -> String {
+    list.iter()
+        .map(|(word, count)| format!("{} {}\n", word, count))
+        .collect()
+}
+
+/// How `merge` combines the counts of a word that is on more than one list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combine {
+    /// Adds the counts up.
+    Sum,
+    /// Keeps the count from the last list with the word.
+    Replace,
+}
+
+/// Every word on any of `lists`, in the order they first appear.
+pub fn merge(lists: &[WordList], combine: Combine) -> WordList {
+    let mut merged: WordList = Vec::new();
+    let mut at: HashMap<String, usize> = HashMap::new();
+    for (word, count) in lists.iter().flatten() {
+        match at.get(word) {
+            Some(&i) => match combine {
+                Combine::Sum => merged[i].1 += count,
+                Combine::Replace => merged[i].1 = *count,
+            },
+            None => {
+                at.insert(word.clone(), merged.len());
+                merged.push((word.clone(), *count));
+            }
+        }
+    }
+    merged
+}
+
+/// `list` with each count replaced by the weight `prior` gives the word, scaled so that the
+/// counts add up to about `total`. Words with too little weight to round up get a count of 0.
+pub fn reweight<P: AnswerPrior + ?Sized>(
+    list: &[(String, usize)],
+    prior: &P,
+    total: usize,
+) -> WordList {
+    let sum: usize = list.iter().map(|&(_, c)| c).sum();
+    let weights: Vec<f64> = list.iter().map(|(w, c)| prior.weight(w, *c, sum)).collect();
+    let weight: f64 = weights.iter().sum();
+    let scale = if weight > 0.0 { total as f64 / weight } else { 0.0 };
+    list.iter()
+        .zip(weights)
+        .map(|((word, _), w)| (word.clone(), (w * scale).round() as usize))
+        .collect()
+}
+
+/// How one word list differs from another.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Diff {
+    /// Words only on the new list, with their counts.
+    pub added: WordList,
+    /// Words only on the old list, with their counts.
+    pub removed: WordList,
+    /// Words on both lists with different counts: the old count, then the new.
+    pub changed: Vec<(String, usize, usize)>,
+}
+
+impl Diff {
+    /// How `new` differs from `old`, each part in the order of the list it comes from.
+    pub fn new(old: &[(String, usize)], new: &[(String, usize)]) -> Self {
+        let counts: HashMap<&str, usize> = old.iter().map(|(w, c)| (w.as_str(), *c)).collect();
+        let words: HashSet<&str> = new.iter().map(|(w, _)| w.as_str()).collect();
+        let mut diff = Diff::default();
+        for (word, count) in new {
+            match counts.get(word.as_str()) {
+                None => diff.added.push((word.clone(), *count)),
+                Some(&before) if before != *count => {
+                    diff.changed.push((word.clone(), before, *count))
+                }
+                Some(_) => {}
+            }
+        }
+        diff.removed = old
+            .iter()
+            .filter(|(word, _)| !words.contains(word.as_str()))
+            .cloned()
+            .collect();
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Writes `+` before added words, `-` before removed ones and `~` before changed ones.
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (word, count) in &self.added {
+            writeln!(f, "+ {} {}", word, count)?;
+        }
+        for (word, count) in &self.removed {
+            writeln!(f, "- {} {}", word, count)?;
+        }
+        for (word, before, after) in &self.changed {
+            writeln!(f, "~ {} {} -> {}", word, before, after)?;
+        }
+        writeln!(
+            f,
+            "{} added, {} removed, {} changed",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        )
+    }
+}
+
+/// A summary of the frequency counts of a word list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub words: usize,
+    pub total: usize,
+    pub min: usize,
+    pub median: usize,
+    pub max: usize,
+    /// How many words have a count of 0.
+    pub zero: usize,
+    /// `decades[n]` is how many words have a count from `10^n` up to but not including
+    /// `10^(n + 1)`.
+    pub decades: Vec<usize>,
+    /// The entropy, in bits, of which word is the answer if words are as likely as they are
+    /// frequent.
+    pub entropy: f64,
+}
+
+impl Stats {
+    /// Summarises `list`, which mustn't be empty.
+    pub fn new(list: &[(String, usize)]) -> Self {
+        assert!(!list.is_empty(), "an empty list has no statistics");
+        let mut counts: Vec<usize> = list.iter().map(|&(_, c)| c).collect();
+        counts.sort_unstable();
+        let mut decades = Vec::new();
+        for &c in counts.iter().filter(|&&c| c > 0) {
+            let decade = c.ilog10() as usize;
+            if decade >= decades.len() {
+                decades.resize(decade + 1, 0);
+            }
+            decades[decade] += 1;
+        }
+        Self {
+            words: counts.len(),
+            total: counts.iter().sum(),
+            min: counts[0],
+            median: counts[counts.len() / 2],
+            max: counts[counts.len() - 1],
+            zero: counts.iter().filter(|&&c| c == 0).count(),
+            decades,
+            entropy: remaining_entropy(counts.iter().map(|&c| c as f64)),
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} words, total frequency {}, min {}, median {}, max {}",
+            self.words, self.total, self.min, self.median, self.max
+        )?;
+        writeln!(f, "  frequency  words")?;
+        if self.zero > 0 {
+            writeln!(f, "{:>11}  {}", 0, self.zero)?;
+        }
+        for (n, &count) in self.decades.iter().enumerate() {
+            writeln!(f, "{:>11}  {}", format!("1e{}+", n), count)?;
+        }
+        writeln!(
+            f,
+            "{:.2} bits of entropy, of {:.2} if every word were as likely",
+            self.entropy,
+            (self.words as f64).log2()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(words: &[(&str, usize)]) -> WordList {
+        words.iter().map(|&(w, c)| (w.to_string(), c)).collect()
+    }
+
+    #[test]
+    fn finds_problems() {
+        let text = "cigar 10\nrebut\ncigar 3\nsissy x\nhumphs 4\nAwake 5\nblush 2\n";
+        let problems = validate(text, Some("cigar blush focal"));
+        assert_eq!(
+            problems,
+            [
+                Problem::BadLine {
+                    line: 2,
+                    text: "rebut".to_string()
+                },
+                Problem::Duplicate {
+                    line: 3,
+                    word: "cigar".to_string(),
+                    first: 1
+                },
+                Problem::BadLine {
+                    line: 4,
+                    text: "sissy x".to_string()
+                },
+                Problem::WrongLength {
+                    line: 5,
+                    word: "humphs".to_string()
+                },
+                Problem::NotLowercase {
+                    line: 6,
+                    word: "Awake".to_string()
+                },
+                Problem::MissingAnswer {
+                    word: "focal".to_string()
+                },
+            ]
+        );
+        assert!(validate("señor 3\n", None).is_empty());
+        assert_eq!(parse("cigar 1\nrebut 2\n"), Ok(list(&[("cigar", 1), ("rebut", 2)])));
+        assert!(parse(text).is_err());
+    }
+
+    #[test]
+    fn the_dictionary_is_valid() {
+        let text = include_str!("../dictionary.txt");
+        let answers = include_str!("../answers.txt");
+        assert_eq!(validate(text, Some(answers)), []);
+        assert_eq!(format(&parse(text).unwrap()), text);
+    }
+
+    #[test]
+    fn merges_and_diffs() {
+        let old = list(&[("cigar", 10), ("rebut", 5), ("sissy", 1)]);
+        let new = list(&[("rebut", 7), ("humph", 2), ("sissy", 1)]);
+        assert_eq!(
+            merge(&[old.clone(), new.clone()], Combine::Sum),
+            list(&[("cigar", 10), ("rebut", 12), ("sissy", 2), ("humph", 2)])
+        );
+        assert_eq!(
+            merge(&[old.clone(), new.clone()], Combine::Replace),
+            list(&[("cigar", 10), ("rebut", 7), ("sissy", 1), ("humph", 2)])
+        );
+
+        let diff = Diff::new(&old, &new);
+        assert_eq!(diff.added, list(&[("humph", 2)]));
+        assert_eq!(diff.removed, list(&[("cigar", 10)]));
+        assert_eq!(diff.changed, [("rebut".to_string(), 5, 7)]);
+        assert!(Diff::new(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn reweights() {
+        use crate::prior::{RawFrequency, Uniform};
+        let words = list(&[("cigar", 10), ("rebut", 5), ("sissy", 1)]);
+        assert_eq!(
+            reweight(&words, &Uniform, 30),
+            list(&[("cigar", 10), ("rebut", 10), ("sissy", 10)])
+        );
+        assert_eq!(reweight(&words, &RawFrequency, 16), words);
+        assert_eq!(
+            reweight(&words, &RawFrequency, 160),
+            list(&[("cigar", 100), ("rebut", 50), ("sissy", 10)])
+        );
+    }
+
+    #[test]
+    fn summarises_counts() {
+        let stats = Stats::new(&list(&[("a", 0), ("b", 5), ("c", 50), ("d", 55), ("e", 1000)]));
+        assert_eq!((stats.words, stats.total), (5, 1110));
+        assert_eq!((stats.min, stats.median, stats.max), (0, 50, 1000));
+        assert_eq!(stats.zero, 1);
+        assert_eq!(stats.decades, [1, 2, 0, 1]);
+        let uniform = Stats::new(&list(&[("a", 3), ("b", 3), ("c", 3), ("d", 3)]));
+        assert_eq!(uniform.entropy, 2.0);
+    }
+}
@@ -0,0 +1,42 @@
+//! Plays every guesser on a small Spanish word list, installed in place of the built-in
+//! dictionary. Installing is once per process, so this is a test binary of its own.
+
+use logus::algorithms::*;
+use logus::locale::Locale;
+use logus::{Guesser, Wordle};
+
+fn solves_every_word<G: Guesser>(w: &Wordle, name: &str, mut make: impl FnMut() -> G) {
+    for &(word, _) in logus::dictionary() {
+        let guesses = w.play(word, make());
+        assert!(guesses.is_some(), "{} didn't guess '{}'", name, word);
+    }
+}
+
+#[test]
+fn plays_spanish() {
+    let locale = Locale::parse(include_str!("locale/es.locale")).unwrap();
+    let words = locale.load(include_str!("locale/es.txt")).unwrap();
+    assert_eq!(words.len(), 45);
+    logus::install_dictionary(words.clone()).unwrap();
+    assert!(logus::install_dictionary(words).is_err());
+
+    let w = Wordle::new();
+    let opener = logus::opener();
+    assert!(w.word(opener).is_some());
+    let senor = w.word(&locale.encode("señor").unwrap()).unwrap();
+    assert_eq!(locale.decode(senor), "señor");
+    assert!(w.word(&locale.encode("árbol").unwrap()).is_some());
+
+    solves_every_word(&w, "naive", Naive::new);
+    solves_every_word(&w, "allocs", Allocs::new);
+    solves_every_word(&w, "vecrem", Vecrem::new);
+    solves_every_word(&w, "once", OnceInit::new);
+    solves_every_word(&w, "precalc", Precalc::new);
+    solves_every_word(&w, "weight", Weight::new);
+    solves_every_word(&w, "enum", Enumerate::new);
+    solves_every_word(&w, "cutoff", Cutoff::new);
+    solves_every_word(&w, "popular", Popular::new);
+    solves_every_word(&w, "sigmoid", Sigmoid::new);
+    solves_every_word(&w, "mcts", Mcts::new);
+    solves_every_word(&w, "probing cutoff", || Cutoff::new().probing());
+}